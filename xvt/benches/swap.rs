@@ -0,0 +1,24 @@
+//! 比较标量与 SIMD 字节序交换复制的耗时，以 `cargo bench --features simd` 运行。
+
+use std::hint::black_box;
+use std::time::Instant;
+use xvt::{swap_bytes_u16, swap_bytes_u16_scalar};
+
+const N: usize = 65536;
+const ROUNDS: u32 = 2000;
+
+fn bench(name: &str, f: fn(&mut [u16], &[u16])) {
+    let src: Vec<u16> = (0..N as u32).map(|i| i as u16).collect();
+    let mut dst = vec![0u16; N];
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f(black_box(&mut dst), black_box(&src));
+    }
+    let per_round = start.elapsed() / ROUNDS;
+    println!("{:<8} {:>10.2?} / {} 个值", name, per_round, N);
+}
+
+fn main() {
+    bench("scalar", swap_bytes_u16_scalar);
+    bench("dispatch", swap_bytes_u16);
+}
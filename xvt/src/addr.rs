@@ -0,0 +1,132 @@
+use crate::ValueTable;
+use paste::paste;
+
+/// 一个代表带区域类型的地址的特性。
+///
+/// 存储表中每个区域都独立使用完整的 `u16` 地址空间，原始 `u16` 地址无法表明它属于哪个区域；
+/// 实现该特性的地址类型将区域与值类型绑定在一起，由类型系统防止跨区域的误用。
+///
+/// # Examples
+///
+/// ```
+/// use xvt::{BitAddr, U16Addr, ValueTable};
+///
+/// let vt = ValueTable::new();
+/// vt.set(U16Addr(0x0010), 0x1234);
+/// vt.set(BitAddr(0x0010), true);
+/// assert_eq!(vt.get(U16Addr(0x0010)), 0x1234);
+/// assert!(vt.get(BitAddr(0x0010)));
+/// ```
+///
+/// 单比特地址不能用于读取 16 位值：
+///
+/// ```compile_fail
+/// use xvt::{BitAddr, ValueTable};
+///
+/// let vt = ValueTable::new();
+/// let v: u16 = vt.get(BitAddr(0x0010));
+/// ```
+///
+/// ```compile_fail
+/// use xvt::{BitAddr, ValueTable};
+///
+/// let vt = ValueTable::new();
+/// vt.get_u16(BitAddr(0x0010));
+/// ```
+pub trait Address: Copy {
+    /// 地址上存储的值的类型。
+    type Value;
+
+    /// 从存储表 `vt` 中读取该地址的值。
+    fn get(self, vt: &ValueTable) -> Self::Value;
+
+    /// 将值 `val` 写入存储表 `vt` 中的该地址。
+    fn set(self, vt: &ValueTable, val: Self::Value);
+}
+
+/// 单比特区域中的地址。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitAddr(pub u16);
+
+impl Address for BitAddr {
+    type Value = bool;
+
+    fn get(self, vt: &ValueTable) -> bool {
+        vt.get_bit(self.0)
+    }
+
+    fn set(self, vt: &ValueTable, val: bool) {
+        if val {
+            vt.set_bit(self.0)
+        } else {
+            vt.clear_bit(self.0)
+        }
+    }
+}
+
+macro_rules! impl_addr {
+    ($t:ty, $name:ident) => {
+        paste! {
+            #[doc = "存放类型为 `" $t "` 的值的地址。"]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name(pub u16);
+
+            impl Address for $name {
+                type Value = $t;
+
+                fn get(self, vt: &ValueTable) -> $t {
+                    vt.[<get_ $t>](self.0)
+                }
+
+                fn set(self, vt: &ValueTable, val: $t) {
+                    vt.[<set_ $t>](self.0, val)
+                }
+            }
+        }
+    };
+}
+
+impl_addr!(i8, I8Addr);
+impl_addr!(u8, U8Addr);
+impl_addr!(i16, I16Addr);
+impl_addr!(u16, U16Addr);
+impl_addr!(i32, I32Addr);
+impl_addr!(u32, U32Addr);
+impl_addr!(i64, I64Addr);
+impl_addr!(u64, U64Addr);
+impl_addr!(f32, F32Addr);
+impl_addr!(f64, F64Addr);
+impl_addr!(i128, I128Addr);
+impl_addr!(u128, U128Addr);
+
+impl ValueTable {
+    /// 获取带类型地址 `addr` 的值。
+    pub fn get<A: Address>(&self, addr: A) -> A::Value {
+        addr.get(self)
+    }
+
+    /// 设置带类型地址 `addr` 的值。
+    pub fn set<A: Address>(&self, addr: A, val: A::Value) {
+        addr.set(self, val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed() {
+        let vt = ValueTable::new();
+        vt.set(I16Addr(0x0001), -2);
+        vt.set(F64Addr(0xFFFF), 0.25);
+        vt.set(BitAddr(0x0001), true);
+        assert_eq!(vt.get_u16(0x0001), 0xFFFE);
+        assert_eq!(vt.get(U16Addr(0x0001)), 0xFFFE);
+        assert_eq!(vt.get(F64Addr(0xFFFF)), 0.25);
+        assert_eq!(vt.get(U32Addr(0x0001)), 0);
+        assert!(vt.get(BitAddr(0x0001)));
+        vt.set(BitAddr(0x0001), false);
+        assert!(!vt.get_bit(0x0001));
+    }
+}
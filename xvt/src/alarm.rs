@@ -0,0 +1,70 @@
+use crate::ValueTable;
+
+/// 一个代表阈值告警方向的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossDirection {
+    /// 值高于阈值时告警。
+    Above,
+    /// 值低于阈值时告警。
+    Below,
+}
+
+impl ValueTable {
+    /// 比较指定地址 `addr` 类型为 `u16` 的值与阈值 `threshold`，据此设置或清除告警位 `alarm_bit`。
+    ///
+    /// 告警位自身保存了上一次比较的结果，返回值表示本次比较是否使告警位发生了变化，
+    /// 只关心越限边沿的调用方可据此触发一次性动作。
+    pub fn check_threshold_u16(
+        &self,
+        addr: u16,
+        threshold: u16,
+        alarm_bit: u16,
+        direction: CrossDirection,
+    ) -> bool {
+        let val = self.get_u16(addr);
+        let active = match direction {
+            CrossDirection::Above => val > threshold,
+            CrossDirection::Below => val < threshold,
+        };
+        if active == self.get_bit(alarm_bit) {
+            return false;
+        }
+        if active {
+            self.set_bit(alarm_bit);
+        } else {
+            self.clear_bit(alarm_bit);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threshold_u16() {
+        let vt = ValueTable::new();
+        let check = |dir| vt.check_threshold_u16(0x0040, 100, 0x0010, dir);
+
+        vt.set_u16(0x0040, 50);
+        assert!(!check(CrossDirection::Above));
+        assert!(!vt.get_bit(0x0010));
+        vt.set_u16(0x0040, 101);
+        assert!(check(CrossDirection::Above));
+        assert!(vt.get_bit(0x0010));
+        assert!(!check(CrossDirection::Above));
+        assert!(vt.get_bit(0x0010));
+        vt.set_u16(0x0040, 100);
+        assert!(check(CrossDirection::Above));
+        assert!(!vt.get_bit(0x0010));
+
+        vt.set_u16(0x0040, 99);
+        assert!(check(CrossDirection::Below));
+        assert!(vt.get_bit(0x0010));
+        assert!(!check(CrossDirection::Below));
+        vt.set_u16(0x0040, 150);
+        assert!(check(CrossDirection::Below));
+        assert!(!vt.get_bit(0x0010));
+    }
+}
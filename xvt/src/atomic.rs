@@ -0,0 +1,537 @@
+use crate::{Region, ValueTable};
+use alloc::collections::BTreeMap;
+use core::sync::atomic::{AtomicU16, AtomicU32, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use paste::paste;
+
+macro_rules! impl_atomic {
+    ($t:ty, $a:ty, $region:expr) => {
+        paste! {
+            #[doc = "获取指定地址 `addr` 类型为 `" $t "` 的值的原子视图，超出区域容量时返回 `None`。"]
+            pub(crate) fn [<atomic_ $t>](&self, addr: u16) -> Option<&$a> {
+                if addr as usize >= self.region_capacity($region) {
+                    return None;
+                }
+                unsafe { Some(&*self.region_ptr($region).cast::<$a>().add(addr as usize)) }
+            }
+
+            #[doc = "原子地将 `delta` 加到指定地址 `addr` 类型为 `" $t "` 的值上，溢出时回绕，返回相加前的值。"]
+            ///
+            /// 使用 `SeqCst` 内存顺序；超出区域容量时不做任何操作并返回 `0`。值发生变化时
+            /// 与 `set_*` 系列一样会触发回调和脏标记。
+            pub fn [<fetch_add_ $t>](&self, addr: u16, delta: $t) -> $t {
+                let Some(a) = self.[<atomic_ $t>](addr) else {
+                    return 0;
+                };
+                let prev = a.fetch_add(delta, Ordering::SeqCst);
+                self.on_written($region, addr, delta != 0);
+                prev
+            }
+
+            #[doc = "原子地将 `delta` 加到指定地址 `addr` 类型为 `" $t "` 的计数值上，溢出时回绕，返回相加后的值。"]
+            ///
+            /// 使用 `SeqCst` 内存顺序；超出区域容量时不做任何操作并返回 `0`。值发生变化时
+            /// 与 `set_*` 系列一样会触发回调和脏标记。
+            pub fn [<add_ $t>](&self, addr: u16, delta: $t) -> $t {
+                let Some(a) = self.[<atomic_ $t>](addr) else {
+                    return 0;
+                };
+                let new = a.fetch_add(delta, Ordering::SeqCst).wrapping_add(delta);
+                self.on_written($region, addr, delta != 0);
+                new
+            }
+
+            #[doc = "原子地将 `delta` 加到指定地址 `addr` 类型为 `" $t "` 的计数值上，溢出时停留在 `" $t "::MAX`，返回相加后的值。"]
+            ///
+            /// 使用 `SeqCst` 内存顺序；超出区域容量时不做任何操作并返回 `0`。值发生变化时
+            /// 与 `set_*` 系列一样会触发回调和脏标记。
+            pub fn [<saturating_add_ $t>](&self, addr: u16, delta: $t) -> $t {
+                let Some(a) = self.[<atomic_ $t>](addr) else {
+                    return 0;
+                };
+                let prev = a
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| Some(v.saturating_add(delta)))
+                    .unwrap();
+                let new = prev.saturating_add(delta);
+                self.on_written($region, addr, new != prev);
+                new
+            }
+
+            #[doc = "若指定地址 `addr` 类型为 `" $t "` 的值等于 `current`，则原子地将其替换为 `new`。"]
+            ///
+            /// 成功时返回 `Ok` 包含原值，否则返回 `Err` 包含当前值；使用 `SeqCst` 内存顺序。
+            /// 超出区域容量的地址视为值恒为 `0` 且不可写入，总是返回 `Err(0)`。替换成功时
+            /// 与 `set_*` 系列一样会触发回调和脏标记。
+            pub fn [<compare_exchange_ $t>](&self, addr: u16, current: $t, new: $t) -> Result<$t, $t> {
+                let Some(a) = self.[<atomic_ $t>](addr) else {
+                    return Err(0);
+                };
+                let res = a.compare_exchange(current, new, Ordering::SeqCst, Ordering::SeqCst);
+                if res.is_ok() {
+                    self.on_written($region, addr, current != new);
+                }
+                res
+            }
+        }
+    };
+}
+
+impl ValueTable {
+    /// 获取单比特地址 `addr` 所在字的原子视图及其位掩码，超出区域容量时返回 `None`。
+    fn atomic_bit_word(&self, addr: u16) -> Option<(&AtomicUsize, usize)> {
+        if addr as usize >= self.region_capacity(Region::Bit) {
+            return None;
+        }
+        let (offset, mask) = Self::bit_location(addr);
+        let word = unsafe {
+            &*self
+                .region_ptr(Region::Bit)
+                .cast::<AtomicUsize>()
+                .add(offset)
+        };
+        Some((word, mask))
+    }
+
+    impl_atomic!(u8, AtomicU8, Region::Bits8);
+    impl_atomic!(u16, AtomicU16, Region::Bits16);
+    impl_atomic!(u32, AtomicU32, Region::Bits32);
+    impl_atomic!(u64, AtomicU64, Region::Bits64);
+
+    /// 原子地设置指定地址 `addr` 的单比特值，返回设置前的值。
+    ///
+    /// 与 [`ValueTable::set_bit`] 不同，并发修改同一个字中的其他位不会丢失更新；使用 `SeqCst` 内存顺序。
+    /// 与 `set_bit` 一样会触发回调和脏标记，下同。
+    pub fn set_bit_atomic(&self, addr: u16) -> bool {
+        let Some((word, mask)) = self.atomic_bit_word(addr) else {
+            return false;
+        };
+        let prev = word.fetch_or(mask, Ordering::SeqCst) & mask != 0;
+        self.on_written(Region::Bit, addr, !prev);
+        prev
+    }
+
+    /// 原子地清除指定地址 `addr` 的单比特值，返回清除前的值。
+    ///
+    /// 使用 `SeqCst` 内存顺序；超出区域容量时不做任何操作并返回 `false`，下同。
+    pub fn clear_bit_atomic(&self, addr: u16) -> bool {
+        let Some((word, mask)) = self.atomic_bit_word(addr) else {
+            return false;
+        };
+        let prev = word.fetch_and(!mask, Ordering::SeqCst) & mask != 0;
+        self.on_written(Region::Bit, addr, prev);
+        prev
+    }
+
+    /// 原子地翻转指定地址 `addr` 的单比特值，返回翻转前的值。
+    ///
+    /// 使用 `SeqCst` 内存顺序。
+    pub fn toggle_bit_atomic(&self, addr: u16) -> bool {
+        let Some((word, mask)) = self.atomic_bit_word(addr) else {
+            return false;
+        };
+        let prev = word.fetch_xor(mask, Ordering::SeqCst) & mask != 0;
+        self.on_written(Region::Bit, addr, true);
+        prev
+    }
+
+    /// 原子地将指定地址 `addr` 类型为 `u16` 的值循环左移 `n` 位，返回移位后的值。
+    ///
+    /// 与 `set_*` 系列一样会触发回调和脏标记，下同。
+    pub fn rotate_left_u16(&self, addr: u16, n: u32) -> u16 {
+        let Some(a) = self.atomic_u16(addr) else {
+            return 0;
+        };
+        let prev = a
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| {
+                Some(v.rotate_left(n))
+            })
+            .unwrap();
+        let new = prev.rotate_left(n);
+        self.on_written(Region::Bits16, addr, new != prev);
+        new
+    }
+
+    /// 原子地将指定地址 `addr` 类型为 `u16` 的值循环右移 `n` 位，返回移位后的值。
+    pub fn rotate_right_u16(&self, addr: u16, n: u32) -> u16 {
+        let Some(a) = self.atomic_u16(addr) else {
+            return 0;
+        };
+        let prev = a
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| {
+                Some(v.rotate_right(n))
+            })
+            .unwrap();
+        let new = prev.rotate_right(n);
+        self.on_written(Region::Bits16, addr, new != prev);
+        new
+    }
+
+    /// 原子地按 Modbus 掩码写寄存器（功能码 `0x16`）的规则修改指定地址 `addr` 类型为 `u16` 的值，
+    /// 返回修改后的值。
+    ///
+    /// 新值为 `(current & and_mask) | (or_mask & !and_mask)`；使用 `SeqCst` 内存顺序，
+    /// 超出区域容量时不做任何操作并返回 `0`。与 `set_*` 系列一样会触发回调和脏标记。
+    pub fn mask_write_u16(&self, addr: u16, and_mask: u16, or_mask: u16) -> u16 {
+        let apply = |v: u16| (v & and_mask) | (or_mask & !and_mask);
+        let Some(a) = self.atomic_u16(addr) else {
+            return 0;
+        };
+        let prev = a
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| Some(apply(v)))
+            .unwrap();
+        let new = apply(prev);
+        self.on_written(Region::Bits16, addr, new != prev);
+        new
+    }
+
+    /// 原子地清除 `addrs` 中列出的告警位，返回清除前处于置位状态的个数。
+    ///
+    /// 位于同一个字中的地址会合并为一次原子操作，超出区域容量的地址将被忽略。每个地址与 `clear_bit`
+    /// 一样会触发回调和脏标记。
+    pub fn ack_alarms(&self, addrs: &[u16]) -> u32 {
+        let mut words: BTreeMap<usize, (&AtomicUsize, usize)> = BTreeMap::new();
+        for addr in addrs {
+            if let Some((word, mask)) = self.atomic_bit_word(*addr) {
+                let (offset, _) = Self::bit_location(*addr);
+                words.entry(offset).or_insert((word, 0)).1 |= mask;
+            }
+        }
+        let mut acked = 0;
+        for (offset, (word, mask)) in words {
+            let cleared = word.fetch_and(!mask, Ordering::SeqCst) & mask;
+            acked += cleared.count_ones();
+            let mut x = mask;
+            while x != 0 && self.hooked() {
+                let bit = x.trailing_zeros() as usize;
+                let addr = offset * usize::BITS as usize + bit;
+                self.on_written(Region::Bit, addr as u16, cleared & (1 << bit) != 0);
+                x &= x - 1;
+            }
+        }
+        acked
+    }
+
+    /// 写入指定地址 `value_addr` 类型为 `u16` 的值 `val`，然后以释放语义设置就绪位 `ready_bit`。
+    ///
+    /// 通过 [`ValueTable::read_published_u16`] 观察到就绪位已置位的读取方，保证能读到本次写入的值。
+    ///
+    /// 任一地址超出区域容量时不做任何操作。两个地址都与 `set_*` 系列一样触发回调和脏标记，
+    /// 回调在就绪位置位之后调用。
+    pub fn publish_u16(&self, value_addr: u16, val: u16, ready_bit: u16) {
+        let (Some(a), Some((word, mask))) =
+            (self.atomic_u16(value_addr), self.atomic_bit_word(ready_bit))
+        else {
+            return;
+        };
+        let prev = a.swap(val, Ordering::Relaxed);
+        let was_ready = word.fetch_or(mask, Ordering::Release) & mask != 0;
+        self.on_written(Region::Bits16, value_addr, prev != val);
+        self.on_written(Region::Bit, ready_bit, !was_ready);
+    }
+
+    /// 以获取语义检查就绪位 `ready_bit`，已置位时返回指定地址 `value_addr` 类型为 `u16` 的值。
+    pub fn read_published_u16(&self, value_addr: u16, ready_bit: u16) -> Option<u16> {
+        let (word, mask) = self.atomic_bit_word(ready_bit)?;
+        if word.load(Ordering::Acquire) & mask == 0 {
+            return None;
+        }
+        Some(self.atomic_u16(value_addr)?.load(Ordering::Relaxed))
+    }
+
+    /// 以原子类型切片的形式获取整个 32 位区域。
+    ///
+    /// 存储表按 4096 字节对齐分配，32 位区域的字节偏移是 4 的整数倍，而 [`AtomicU32`]
+    /// 与 `u32` 具有相同的内存布局和对齐要求，因此该转换是有效的；若将来调整区域布局，
+    /// 必须保持 32 位区域的偏移按 4 字节对齐。
+    pub fn as_atomic_u32(&self) -> &[AtomicU32] {
+        unsafe {
+            core::slice::from_raw_parts(
+                self.region_ptr(Region::Bits32).cast::<AtomicU32>(),
+                self.region_capacity(Region::Bits32),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use std::{sync::Arc, thread};
+
+    #[test]
+    fn rotate_u16() {
+        let vt = ValueTable::new();
+        vt.set_u16(0x0010, 0x8001);
+        assert_eq!(vt.rotate_left_u16(0x0010, 1), 0x0003);
+        assert_eq!(vt.rotate_left_u16(0x0010, 15), 0x8001);
+        assert_eq!(vt.rotate_left_u16(0x0010, 16), 0x8001);
+        assert_eq!(vt.rotate_right_u16(0x0010, 1), 0xC000);
+        assert_eq!(vt.rotate_right_u16(0x0010, 15), 0x8001);
+        assert_eq!(vt.rotate_right_u16(0x0010, 16), 0x8001);
+        assert_eq!(vt.get_u16(0x0010), 0x8001);
+
+        let vt = ValueTable::new_tracked();
+        vt.set_u16(0x0010, 0x8001);
+        vt.drain_dirty();
+        vt.rotate_right_u16(0x0010, 4);
+        vt.rotate_left_u16(0x0011, 3);
+        assert_eq!(
+            vt.drain_dirty(),
+            [(Region::Bits16, 0x0010), (Region::Bits16, 0x0011)]
+        );
+    }
+
+    #[test]
+    fn ack_alarms() {
+        let vt = ValueTable::new();
+        for addr in [0x0001, 0x0002, 0x0040, 0x1000] {
+            vt.set_bit(addr);
+        }
+        let addrs = [0x0001, 0x0002, 0x0003, 0x0040, 0x0041, 0x1000, 0x1000];
+        assert_eq!(vt.ack_alarms(&addrs), 4);
+        for addr in addrs {
+            assert!(!vt.get_bit(addr));
+        }
+        assert_eq!(vt.ack_alarms(&addrs), 0);
+
+        let vt = ValueTable::new_tracked();
+        vt.set_bits([0x0001, 0x0100]);
+        vt.drain_dirty();
+        assert_eq!(vt.ack_alarms(&[0x0100, 0x0001, 0x0002]), 2);
+        assert_eq!(
+            vt.drain_dirty(),
+            [
+                (Region::Bit, 0x0001),
+                (Region::Bit, 0x0002),
+                (Region::Bit, 0x0100)
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn publish_u16() {
+        const N: u16 = 200;
+        let vt = Arc::new(ValueTable::new());
+        let writer = {
+            let vt = vt.clone();
+            thread::spawn(move || {
+                for i in 1..=N {
+                    while vt.read_published_u16(0x0100, 0x0005).is_some() {
+                        thread::yield_now();
+                    }
+                    vt.publish_u16(0x0100, i, 0x0005);
+                }
+            })
+        };
+        for i in 1..=N {
+            let val = loop {
+                if let Some(val) = vt.read_published_u16(0x0100, 0x0005) {
+                    break val;
+                }
+                thread::yield_now();
+            };
+            assert_eq!(val, i);
+            assert_eq!(vt.ack_alarms(&[0x0005]), 1);
+        }
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn publish_marks_changes() {
+        let vt = ValueTable::new_tracked();
+        vt.publish_u16(0x0100, 7, 0x0005);
+        assert_eq!(
+            vt.drain_dirty(),
+            [(Region::Bit, 0x0005), (Region::Bits16, 0x0100)]
+        );
+        let vt = ValueTable::new_presence_tracked();
+        vt.publish_u16(0x0100, 0, 0x0005);
+        assert_eq!(vt.get_u16_opt(0x0100), Some(0));
+        assert_eq!(vt.get_bit_opt(0x0005), Some(true));
+    }
+
+    #[test]
+    fn atomic_u32_slice() {
+        let vt = ValueTable::new();
+        let cells = vt.as_atomic_u32();
+        assert_eq!(cells.len(), 65536);
+        vt.set_u32(0xFFFF, 41);
+        let cell = &cells[0xFFFF];
+        let mut cur = cell.load(Ordering::Relaxed);
+        while let Err(v) =
+            cell.compare_exchange_weak(cur, cur * 2 + 1, Ordering::SeqCst, Ordering::Relaxed)
+        {
+            cur = v;
+        }
+        assert_eq!(vt.get_u32(0xFFFF), 83);
+        assert_eq!(vt.get_u32(0xFFFE), 0);
+    }
+
+    #[test]
+    fn bit_atomics() {
+        let vt = ValueTable::new();
+        assert!(!vt.set_bit_atomic(0x0101));
+        assert!(vt.set_bit_atomic(0x0101));
+        assert!(vt.toggle_bit_atomic(0x0101));
+        assert!(!vt.get_bit(0x0101));
+        assert!(!vt.toggle_bit_atomic(0x0101));
+        assert!(vt.clear_bit_atomic(0x0101));
+        assert!(!vt.clear_bit_atomic(0x0101));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn bit_atomics_stress() {
+        const THREADS: u16 = 4;
+        let vt = Arc::new(ValueTable::new());
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let vt = vt.clone();
+                thread::spawn(move || {
+                    for round in 0..200 {
+                        for addr in (t..64).step_by(THREADS as usize) {
+                            vt.toggle_bit_atomic(addr);
+                            if round % 2 == 1 {
+                                vt.set_bit_atomic(addr);
+                            }
+                        }
+                        thread::yield_now();
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        for addr in 0..64 {
+            assert!(vt.get_bit(addr));
+        }
+        assert!(!vt.get_bit(64));
+    }
+
+    #[test]
+    fn integer_atomics() {
+        let vt = ValueTable::new();
+        vt.set_u32(0x0020, u32::MAX);
+        assert_eq!(vt.fetch_add_u32(0x0020, 2), u32::MAX);
+        assert_eq!(vt.get_u32(0x0020), 1);
+        assert_eq!(vt.compare_exchange_u32(0x0020, 1, 5), Ok(1));
+        assert_eq!(vt.compare_exchange_u32(0x0020, 1, 7), Err(5));
+        assert_eq!(vt.fetch_add_u8(0xFFFF, 3), 0);
+        assert_eq!(vt.fetch_add_u64(0xFFFF, 4), 0);
+        assert_eq!(vt.compare_exchange_u16(0x0000, 0, 9), Ok(0));
+        assert_eq!(
+            (vt.get_u8(0xFFFF), vt.get_u64(0xFFFF), vt.get_u16(0)),
+            (3, 4, 9)
+        );
+    }
+
+    #[test]
+    fn counters() {
+        let vt = ValueTable::new();
+        vt.set_u32(0x0001, u32::MAX - 1);
+        assert_eq!(vt.add_u32(0x0001, 3), 1);
+        assert_eq!(vt.get_u32(0x0001), 1);
+
+        vt.set_u32(0x0002, u32::MAX - 1);
+        assert_eq!(vt.saturating_add_u32(0x0002, 3), u32::MAX);
+        assert_eq!(vt.saturating_add_u32(0x0002, 1), u32::MAX);
+        assert_eq!(vt.get_u32(0x0002), u32::MAX);
+
+        assert_eq!(vt.add_u8(0x0000, 200), 200);
+        assert_eq!(vt.saturating_add_u8(0x0000, 100), u8::MAX);
+        assert_eq!(vt.add_u16(0x0000, 1), 1);
+        assert_eq!(vt.saturating_add_u64(0x0000, u64::MAX), u64::MAX);
+
+        let small = ValueTable::with_regions(crate::RegionConfig::empty());
+        assert_eq!(small.add_u32(0x0000, 1), 0);
+        assert_eq!(small.saturating_add_u32(0x0000, 1), 0);
+    }
+
+    #[test]
+    fn counters_mark_changes() {
+        let vt = ValueTable::new_tracked();
+        vt.add_u32(0x0001, 1);
+        vt.add_u32(0x0002, 0);
+        vt.set_u16(0x0003, u16::MAX);
+        vt.drain_dirty();
+        vt.saturating_add_u16(0x0003, 1);
+        vt.saturating_add_u16(0x0004, 1);
+        assert_eq!(vt.get_u32(0x0001), 1);
+        assert_eq!(
+            vt.drain_dirty(),
+            [(Region::Bits16, 0x0003), (Region::Bits16, 0x0004)]
+        );
+
+        let vt = ValueTable::new_presence_tracked();
+        assert_eq!(vt.get_u32_opt(0x0001), None);
+        vt.add_u32(0x0001, 5);
+        assert_eq!(vt.get_u32_opt(0x0001), Some(5));
+    }
+
+    #[test]
+    fn atomics_mark_changes() {
+        let vt = ValueTable::new_tracked();
+        vt.fetch_add_u64(0x0001, 2);
+        assert_eq!(vt.compare_exchange_u8(0x0002, 0, 7), Ok(0));
+        assert_eq!(vt.compare_exchange_u8(0x0003, 1, 7), Err(0));
+        vt.set_bit_atomic(0x0010);
+        vt.clear_bit_atomic(0x0011);
+        vt.toggle_bit_atomic(0x0012);
+        assert_eq!(
+            vt.drain_dirty(),
+            [
+                (Region::Bit, 0x0010),
+                (Region::Bit, 0x0011),
+                (Region::Bit, 0x0012),
+                (Region::Bits8, 0x0002),
+                (Region::Bits64, 0x0001),
+            ]
+        );
+
+        let vt = ValueTable::new_presence_tracked();
+        vt.fetch_add_u16(0x0001, 1);
+        vt.toggle_bit_atomic(0x0002);
+        assert_eq!(vt.get_u16_opt(0x0001), Some(1));
+        assert_eq!(vt.get_bit_opt(0x0002), Some(true));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn atomics_notify() {
+        use std::sync::{Arc, Mutex};
+
+        let vt = ValueTable::new();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        vt.on_change(Arc::new(move |region, addr| {
+            sink.lock().unwrap().push((region, addr))
+        }));
+        vt.fetch_add_u32(0x0001, 0);
+        vt.fetch_add_u32(0x0001, 1);
+        vt.set_bit_atomic(0x0005);
+        vt.set_bit_atomic(0x0005);
+        assert_eq!(
+            *events.lock().unwrap(),
+            [(Region::Bits32, 0x0001), (Region::Bit, 0x0005)]
+        );
+    }
+
+    #[test]
+    fn mask_write() {
+        let vt = ValueTable::new();
+        vt.set_u16(0x0004, 0x12);
+        assert_eq!(vt.mask_write_u16(0x0004, 0xF2, 0x25), 0x17);
+        assert_eq!(vt.get_u16(0x0004), 0x17);
+        assert_eq!(vt.mask_write_u16(0x0004, 0xFFFF, 0x00FF), 0x17);
+        assert_eq!(vt.mask_write_u16(0x0004, 0x0000, 0xABCD), 0xABCD);
+
+        let vt = ValueTable::new_tracked();
+        vt.mask_write_u16(0x0004, 0x0000, 0x0001);
+        assert_eq!(vt.drain_dirty(), [(Region::Bits16, 0x0004)]);
+        let vt = ValueTable::new_presence_tracked();
+        vt.mask_write_u16(0x0004, 0x0000, 0x0001);
+        assert_eq!(vt.get_u16_opt(0x0004), Some(1));
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{Region, ValueTable};
+use paste::paste;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// 一个代表审计记录中访问类型的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditKind {
+    /// 读取，`old` 与 `new` 均为读到的值。
+    Read,
+    /// 写入。
+    Write,
+}
+
+/// 一条审计记录。
+///
+/// 值以其原始位零扩展为 `u128` 保存：有符号整数按同宽度的无符号整数保存，浮点值按
+/// `to_bits` 的结果保存，单比特值为 `0` 或 `1`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// 访问类型。
+    pub kind: AuditKind,
+    /// 值所在的区域。
+    pub region: Region,
+    /// 值的地址。
+    pub addr: u16,
+    /// 访问前的值。
+    pub old: u128,
+    /// 访问后的值。
+    pub new: u128,
+    /// 访问发生的时刻。
+    pub at: Instant,
+}
+
+/// 将值的原始位零扩展为 `u128`。
+trait RawBits {
+    fn raw_bits(self) -> u128;
+}
+
+macro_rules! impl_raw_bits {
+    ($($t:ty => |$v:ident| $e:expr),* $(,)?) => {
+        $(impl RawBits for $t {
+            fn raw_bits(self) -> u128 {
+                let $v = self;
+                $e
+            }
+        })*
+    };
+}
+
+impl_raw_bits! {
+    bool => |v| v as u128,
+    i8 => |v| v as u8 as u128,
+    u8 => |v| v as u128,
+    i16 => |v| v as u16 as u128,
+    u16 => |v| v as u128,
+    i32 => |v| v as u32 as u128,
+    u32 => |v| v as u128,
+    i64 => |v| v as u64 as u128,
+    u64 => |v| v as u128,
+    f32 => |v| v.to_bits() as u128,
+    f64 => |v| v.to_bits() as u128,
+    i128 => |v| v as u128,
+    u128 => |v| v,
+}
+
+/// 一个记录每次写入的多种值存储表包装类型。
+///
+/// 通过本类型的 `set_*` 系列方法写入时，写入前后的值与时刻被记入容量有限的环形缓冲区，
+/// 缓冲区满时丢弃最早的记录。开启 [`AuditedValueTable::set_log_reads`] 后，`get_*` 系列
+/// 方法的读取也会被记录。经 [`AuditedValueTable::inner`] 直接访问存储表不会留下记录。
+///
+/// # Examples
+///
+/// ```
+/// use xvt::{AuditedValueTable, ValueTable};
+///
+/// let vt = AuditedValueTable::new(ValueTable::new(), 16);
+/// vt.set_u16(0x0010, 1);
+/// vt.set_u16(0x0010, 2);
+/// let log = vt.audit_log();
+/// assert_eq!((log[1].old, log[1].new), (1, 2));
+/// ```
+pub struct AuditedValueTable {
+    inner: ValueTable,
+    log: Mutex<VecDeque<AuditEntry>>,
+    capacity: usize,
+    log_reads: AtomicBool,
+}
+
+macro_rules! impl_audited {
+    ($t:ty, $region:ident) => {
+        paste! {
+            #[doc = "获取指定地址 `addr` 类型为 `" $t "` 的值，开启读取记录时记入审计日志。"]
+            pub fn [<get_ $t>](&self, addr: u16) -> $t {
+                let val = self.inner.[<get_ $t>](addr);
+                if self.log_reads.load(Ordering::Relaxed) {
+                    let raw = val.raw_bits();
+                    self.record(AuditKind::Read, Region::$region, addr, raw, raw);
+                }
+                val
+            }
+
+            #[doc = "设置指定地址 `addr` 类型为 `" $t "` 的值，并将写入前后的值记入审计日志。"]
+            pub fn [<set_ $t>](&self, addr: u16, val: $t) {
+                let mut log = self.lock();
+                let old = self.inner.[<get_ $t>](addr).raw_bits();
+                self.inner.[<set_ $t>](addr, val);
+                let new = self.inner.[<get_ $t>](addr).raw_bits();
+                self.push(&mut log, AuditKind::Write, Region::$region, addr, old, new);
+            }
+        }
+    };
+}
+
+impl AuditedValueTable {
+    /// 包装存储表 `inner`，审计日志最多保留 `capacity` 条记录。
+    pub fn new(inner: ValueTable, capacity: usize) -> Self {
+        Self {
+            inner,
+            log: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            log_reads: AtomicBool::new(false),
+        }
+    }
+
+    /// 获取被包装的存储表，经此进行的访问不会被记录。
+    pub fn inner(&self) -> &ValueTable {
+        &self.inner
+    }
+
+    /// 取回被包装的存储表，丢弃审计日志。
+    pub fn into_inner(self) -> ValueTable {
+        self.inner
+    }
+
+    /// 设置是否记录通过 `get_*` 系列方法进行的读取，默认不记录。
+    pub fn set_log_reads(&self, enabled: bool) {
+        self.log_reads.store(enabled, Ordering::Relaxed);
+    }
+
+    /// 按发生顺序复制当前保留的全部审计记录。
+    pub fn audit_log(&self) -> Vec<AuditEntry> {
+        self.lock().iter().copied().collect()
+    }
+
+    /// 清空审计日志。
+    pub fn clear_audit_log(&self) {
+        self.lock().clear();
+    }
+
+    /// 获取指定地址 `addr` 的单比特值，开启读取记录时记入审计日志。
+    pub fn get_bit(&self, addr: u16) -> bool {
+        let val = self.inner.get_bit(addr);
+        if self.log_reads.load(Ordering::Relaxed) {
+            let raw = val.raw_bits();
+            self.record(AuditKind::Read, Region::Bit, addr, raw, raw);
+        }
+        val
+    }
+
+    /// 置位指定地址 `addr` 的单比特值，并将写入前后的值记入审计日志。
+    pub fn set_bit(&self, addr: u16) {
+        self.write_bit(addr, true)
+    }
+
+    /// 清除指定地址 `addr` 的单比特值，并将写入前后的值记入审计日志。
+    pub fn clear_bit(&self, addr: u16) {
+        self.write_bit(addr, false)
+    }
+
+    fn write_bit(&self, addr: u16, val: bool) {
+        let mut log = self.lock();
+        let old = self.inner.get_bit(addr).raw_bits();
+        if val {
+            self.inner.set_bit(addr);
+        } else {
+            self.inner.clear_bit(addr);
+        }
+        let new = self.inner.get_bit(addr).raw_bits();
+        self.push(&mut log, AuditKind::Write, Region::Bit, addr, old, new);
+    }
+
+    impl_audited!(i8, Bits8);
+    impl_audited!(u8, Bits8);
+    impl_audited!(i16, Bits16);
+    impl_audited!(u16, Bits16);
+    impl_audited!(i32, Bits32);
+    impl_audited!(u32, Bits32);
+    impl_audited!(f32, Bits32);
+    impl_audited!(i64, Bits64);
+    impl_audited!(u64, Bits64);
+    impl_audited!(f64, Bits64);
+    impl_audited!(i128, Bits128);
+    impl_audited!(u128, Bits128);
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<AuditEntry>> {
+        self.log.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(&self, kind: AuditKind, region: Region, addr: u16, old: u128, new: u128) {
+        let mut log = self.lock();
+        self.push(&mut log, kind, region, addr, old, new);
+    }
+
+    /// 追加一条记录，写入时在持有日志锁期间完成读旧值、写入与记录，保证记录顺序与写入顺序一致。
+    fn push(
+        &self,
+        log: &mut VecDeque<AuditEntry>,
+        kind: AuditKind,
+        region: Region,
+        addr: u16,
+        old: u128,
+        new: u128,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        if log.len() == self.capacity {
+            log.pop_front();
+        }
+        log.push_back(AuditEntry {
+            kind,
+            region,
+            addr,
+            old,
+            new,
+            at: Instant::now(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_writes_in_order() {
+        let vt = AuditedValueTable::new(ValueTable::new(), 8);
+        vt.set_u16(0x0010, 0x1234);
+        vt.set_bit(0x0003);
+        vt.set_i16(0x0010, -1);
+        vt.set_f32(0x0001, 1.5);
+        vt.clear_bit(0x0003);
+        assert_eq!(vt.get_u16(0x0010), 0xFFFF);
+
+        let log = vt.audit_log();
+        let summary: Vec<_> = log
+            .iter()
+            .map(|e| (e.kind, e.region, e.addr, e.old, e.new))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (AuditKind::Write, Region::Bits16, 0x0010, 0, 0x1234),
+                (AuditKind::Write, Region::Bit, 0x0003, 0, 1),
+                (AuditKind::Write, Region::Bits16, 0x0010, 0x1234, 0xFFFF),
+                (
+                    AuditKind::Write,
+                    Region::Bits32,
+                    0x0001,
+                    0,
+                    1.5f32.to_bits() as u128
+                ),
+                (AuditKind::Write, Region::Bit, 0x0003, 1, 0),
+            ]
+        );
+        assert!(log.windows(2).all(|w| w[0].at <= w[1].at));
+    }
+
+    #[test]
+    fn bounded_and_reads() {
+        let vt = AuditedValueTable::new(ValueTable::new(), 2);
+        for i in 1..=3 {
+            vt.set_u8(0x0000, i);
+        }
+        let log = vt.audit_log();
+        assert_eq!(log.len(), 2);
+        assert_eq!((log[0].old, log[0].new), (1, 2));
+
+        vt.clear_audit_log();
+        vt.set_log_reads(true);
+        assert_eq!(vt.get_u8(0x0000), 3);
+        vt.inner().set_u8(0x0000, 4);
+        let log = vt.audit_log();
+        assert_eq!(log.len(), 1);
+        assert_eq!(
+            (log[0].kind, log[0].old, log[0].new),
+            (AuditKind::Read, 3, 3)
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{RegionConfig, ValueTable};
+use alloc::{boxed::Box, vec::Vec};
+use paste::paste;
+
+/// 写入单个初始值的操作。
+type Seed = Box<dyn FnOnce(&ValueTable)>;
+
+/// 一个在构建时预先写入初始值的存储表构建器。
+///
+/// 初始值按添加的顺序写入，同一地址后添加的值覆盖先添加的值。
+///
+/// # Examples
+///
+/// ```
+/// use xvt::ValueTableBuilder;
+///
+/// let vt = ValueTableBuilder::new()
+///     .bit(0x0001)
+///     .u16(0x0010, 0x1234)
+///     .f32(0x0000, 1.5)
+///     .u16(0x0010, 0x4321)
+///     .build();
+/// assert!(vt.get_bit(0x0001));
+/// assert_eq!(vt.get_u16(0x0010), 0x4321);
+/// assert_eq!(vt.get_f32(0x0000), 1.5);
+/// ```
+pub struct ValueTableBuilder {
+    config: RegionConfig,
+    seeds: Vec<Seed>,
+}
+
+macro_rules! impl_seed {
+    ($t:ty) => {
+        paste! {
+            #[doc = "将地址 `addr` 类型为 `" $t "` 的值初始化为 `val`。"]
+            pub fn $t(self, addr: u16, val: $t) -> Self {
+                self.seed(move |vt| vt.[<set_ $t>](addr, val))
+            }
+        }
+    };
+}
+
+impl ValueTableBuilder {
+    /// 构建一个各区域按 [`RegionConfig::full`] 分配、没有初始值的构建器。
+    pub fn new() -> Self {
+        Self {
+            config: RegionConfig::full(),
+            seeds: Vec::new(),
+        }
+    }
+
+    /// 使用区域配置 `config` 分配存储表。
+    pub fn regions(mut self, config: RegionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    fn seed(mut self, f: impl FnOnce(&ValueTable) + 'static) -> Self {
+        self.seeds.push(Box::new(f));
+        self
+    }
+
+    /// 将地址 `addr` 的单比特值初始化为置位。
+    pub fn bit(self, addr: u16) -> Self {
+        self.seed(move |vt| vt.set_bit(addr))
+    }
+
+    /// 将地址 `addr` 的单比特值初始化为清除，用于覆盖先前的 [`ValueTableBuilder::bit`]。
+    pub fn clear_bit(self, addr: u16) -> Self {
+        self.seed(move |vt| vt.clear_bit(addr))
+    }
+
+    impl_seed!(i8);
+    impl_seed!(u8);
+    impl_seed!(i16);
+    impl_seed!(u16);
+    impl_seed!(i32);
+    impl_seed!(u32);
+    impl_seed!(i64);
+    impl_seed!(u64);
+    impl_seed!(f32);
+    impl_seed!(f64);
+    impl_seed!(i128);
+    impl_seed!(u128);
+
+    /// 分配存储表并依次写入所有初始值。
+    pub fn build(self) -> ValueTable {
+        let vt = ValueTable::with_regions(self.config);
+        for seed in self.seeds {
+            seed(&vt);
+        }
+        vt
+    }
+}
+
+impl Default for ValueTableBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds() {
+        let vt = ValueTableBuilder::new()
+            .bit(0x0000)
+            .bit(0xFFFF)
+            .bit(0x0010)
+            .clear_bit(0x0010)
+            .i8(0x0001, -1)
+            .u8(0x0002, 2)
+            .i16(0x0001, -3)
+            .u16(0x0002, 4)
+            .u16(0x0002, 5)
+            .i32(0x0001, -6)
+            .u32(0xFFFF, 7)
+            .f32(0x0003, 8.5)
+            .i64(0x0001, -9)
+            .u64(0x0002, 10)
+            .f64(0x0003, 11.25)
+            .build();
+        assert!(vt.get_bit(0x0000) && vt.get_bit(0xFFFF));
+        assert!(!vt.get_bit(0x0010));
+        assert_eq!((vt.get_i8(0x0001), vt.get_u8(0x0002)), (-1, 2));
+        assert_eq!((vt.get_i16(0x0001), vt.get_u16(0x0002)), (-3, 5));
+        assert_eq!((vt.get_i32(0x0001), vt.get_u32(0xFFFF)), (-6, 7));
+        assert_eq!(vt.get_f32(0x0003), 8.5);
+        assert_eq!((vt.get_i64(0x0001), vt.get_u64(0x0002)), (-9, 10));
+        assert_eq!(vt.get_f64(0x0003), 11.25);
+        assert_eq!(vt.iter_set_bits().count(), 2);
+    }
+
+    #[test]
+    fn regions() {
+        let vt = ValueTableBuilder::new()
+            .regions(RegionConfig {
+                bits16: 16,
+                ..RegionConfig::empty()
+            })
+            .u16(0x000F, 1)
+            .u16(0x0010, 2)
+            .build();
+        assert_eq!(vt.get_u16s(0x0000, 32).len(), 16);
+        assert_eq!(vt.get_u16(0x000F), 1);
+    }
+}
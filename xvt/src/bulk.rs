@@ -0,0 +1,438 @@
+use crate::{Region, ValueTable, XvtError};
+use alloc::vec::Vec;
+use paste::paste;
+
+macro_rules! impl_fill {
+    ($t:ty, $region:expr) => {
+        paste! {
+            #[doc = "将整个区域中类型为 `" $t "` 的值都设置为 `value`。"]
+            pub fn [<fill_ $t>](&self, value: $t) {
+                let region = $region;
+                let vals = unsafe {
+                    core::slice::from_raw_parts_mut(
+                        self.region_ptr(region).cast::<$t>(),
+                        self.region_capacity(region),
+                    )
+                };
+                vals.fill(value);
+                self.generations.bump(region);
+            }
+        }
+    };
+}
+
+impl ValueTable {
+    impl_fill!(i8, Region::Bits8);
+    impl_fill!(u8, Region::Bits8);
+    impl_fill!(i16, Region::Bits16);
+    impl_fill!(u16, Region::Bits16);
+    impl_fill!(i32, Region::Bits32);
+    impl_fill!(u32, Region::Bits32);
+    impl_fill!(i64, Region::Bits64);
+    impl_fill!(u64, Region::Bits64);
+    impl_fill!(f32, Region::Bits32);
+    impl_fill!(f64, Region::Bits64);
+    impl_fill!(i128, Region::Bits128);
+    impl_fill!(u128, Region::Bits128);
+
+    /// 清除单比特区域中的所有位。
+    pub fn clear_bits_all(&self) {
+        self.clear_region(Region::Bit);
+    }
+
+    /// 设置 `addrs` 中所有地址的单比特值。
+    ///
+    /// 逐个调用 [`ValueTable::set_bit`]，因此会触发变化回调和脏标记，超出区域容量的地址将被忽略。
+    pub fn set_bits<I: IntoIterator<Item = u16>>(&self, addrs: I) {
+        addrs.into_iter().for_each(|addr| self.set_bit(addr));
+    }
+
+    /// 清除 `addrs` 中所有地址的单比特值。
+    ///
+    /// 逐个调用 [`ValueTable::clear_bit`]，因此会触发变化回调和脏标记，超出区域容量的地址将被忽略。
+    pub fn clear_bits<I: IntoIterator<Item = u16>>(&self, addrs: I) {
+        addrs.into_iter().for_each(|addr| self.clear_bit(addr));
+    }
+
+    /// 将单比特区域中从 `start` 开始的 `count` 个位移动 `by` 个位置，空出的位置填 `0`。
+    ///
+    /// `by` 为正时向高地址移动，为负时向低地址移动，移出窗口的位被丢弃，`by` 的绝对值不小于窗口长度时
+    /// 清除整个窗口。窗口超出区域容量的部分被截去；值发生变化的位会触发变化回调和脏标记。
+    pub fn shift_bits(&self, start: u16, count: u16, by: i32) {
+        self.permute_bits(start, count, |i, n| {
+            let src = i as i64 - by as i64;
+            (0..n as i64).contains(&src).then_some(src as usize)
+        });
+    }
+
+    /// 将单比特区域中从 `start` 开始的 `count` 个位循环移动 `by` 个位置，移出窗口的位从另一端移入。
+    ///
+    /// 方向与 [`ValueTable::shift_bits`] 相同，`by` 按窗口长度取模。
+    pub fn rotate_bits(&self, start: u16, count: u16, by: i32) {
+        self.permute_bits(start, count, |i, n| {
+            let k = (by as i64).rem_euclid(n as i64) as usize;
+            Some((i + n - k) % n)
+        });
+    }
+
+    /// 以 `src(i, n)` 给出窗口内第 `i` 位的来源位置重排窗口，`None` 表示填 `0`。
+    fn permute_bits(&self, start: u16, count: u16, src: impl Fn(usize, usize) -> Option<usize>) {
+        let end = (start as usize + count as usize).min(self.region_capacity(Region::Bit));
+        let Some(n) = end.checked_sub(start as usize).filter(|n| *n != 0) else {
+            return;
+        };
+        let old: Vec<bool> = (0..n).map(|i| self.get_bit(start + i as u16)).collect();
+        for (i, cur) in old.iter().enumerate() {
+            let new = src(i, n).is_some_and(|j| old[j]);
+            if new != *cur {
+                let addr = start + i as u16;
+                if new {
+                    self.set_bit(addr);
+                } else {
+                    self.clear_bit(addr);
+                }
+            }
+        }
+    }
+
+    /// 将整个存储表一次性清零。
+    ///
+    /// 与其他批量操作一样直接改写内存，不会触发变化回调或脏标记。
+    pub fn clear_all(&self) {
+        unsafe { core::ptr::write_bytes(self.mem.as_ptr(), 0, self.layout.size()) }
+        self.seed_canaries();
+        self.generations.bump_all();
+    }
+
+    /// 将整个存储表一次性清零，`&mut self` 保证了清零期间没有其他访问。
+    pub fn clear_all_mut(&mut self) {
+        self.clear_all()
+    }
+
+    /// 将区域 `region` 的全部字节清零，不影响其他区域。
+    pub fn clear_region(&self, region: Region) {
+        unsafe { core::ptr::write_bytes(self.region_ptr(region), 0, self.region_len(region)) }
+        self.generations.bump(region);
+    }
+
+    /// 从地址 `start` 开始，每隔 `stride` 个地址设置一个类型为 `u16` 的值。
+    ///
+    /// 超出区域容量的值将被忽略，返回实际写入的值个数；`stride` 为 `0` 时返回错误。
+    pub fn set_u16_strided(
+        &self,
+        start: u16,
+        stride: u16,
+        vals: &[u16],
+    ) -> Result<usize, XvtError> {
+        if stride == 0 {
+            return Err(XvtError::ZeroStride);
+        }
+        let mut written = 0;
+        for (i, val) in vals.iter().enumerate() {
+            let addr = start as usize + i * stride as usize;
+            if addr >= self.region_capacity(Region::Bits16) {
+                break;
+            }
+            self.set_u16(addr as u16, *val);
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// 以字节序列 `pattern` 重复填充区域 `region` 的原始字节。
+    ///
+    /// 区域长度不是 `pattern` 长度的整数倍时，最后一次重复将被截断；`pattern` 为空时不做任何操作。
+    pub fn fill_pattern_u8(&self, region: Region, pattern: &[u8]) {
+        if pattern.is_empty() {
+            return;
+        }
+        let bytes = unsafe {
+            core::slice::from_raw_parts_mut(self.region_ptr(region), self.region_len(region))
+        };
+        for chunk in bytes.chunks_mut(pattern.len()) {
+            chunk.copy_from_slice(&pattern[..chunk.len()]);
+        }
+        self.generations.bump(region);
+    }
+
+    /// 将区域 `src_region` 中从地址 `src_start` 开始的 `byte_len` 个原始字节复制到区域
+    /// `dst_region` 中从地址 `dst_start` 开始的位置，不做任何类型转换。
+    ///
+    /// 地址以对应区域的值为单位，单比特区域以字节为单位；任一区域越界时返回错误且不做任何修改。
+    pub fn reinterpret_copy(
+        &self,
+        src_region: Region,
+        dst_region: Region,
+        src_start: u16,
+        dst_start: u16,
+        byte_len: usize,
+    ) -> Result<(), XvtError> {
+        let src_ofs = src_start as usize * src_region.element_size().max(1);
+        let dst_ofs = dst_start as usize * dst_region.element_size().max(1);
+        let out_of = |ofs: usize, region: Region| {
+            ofs.checked_add(byte_len)
+                .is_none_or(|end| end > self.region_len(region))
+        };
+        if out_of(src_ofs, src_region) || out_of(dst_ofs, dst_region) {
+            return Err(XvtError::OutOfRange);
+        }
+        unsafe {
+            core::ptr::copy(
+                self.region_ptr(src_region).add(src_ofs),
+                self.region_ptr(dst_region).add(dst_ofs),
+                byte_len,
+            );
+        }
+        if byte_len != 0 {
+            self.generations.bump(dst_region);
+        }
+        Ok(())
+    }
+
+    /// 将从地址 `start` 开始 `count` 个类型为 `u16` 的值限制在 `[lo, hi]` 范围内，返回被修改的值个数。
+    ///
+    /// 超出区域容量的部分将被忽略；`lo` 大于 `hi` 时不做任何修改并返回 `0`。
+    pub fn clamp_region_u16(&self, start: u16, count: u16, lo: u16, hi: u16) -> u32 {
+        let n = self
+            .region_capacity(Region::Bits16)
+            .saturating_sub(start as usize)
+            .min(count as usize);
+        if n == 0 || lo > hi {
+            return 0;
+        }
+        let vals = unsafe {
+            core::slice::from_raw_parts_mut(
+                self.region_ptr(Region::Bits16)
+                    .cast::<u16>()
+                    .add(start as usize),
+                n,
+            )
+        };
+        let mut modified = 0;
+        for val in vals {
+            let clamped = (*val).clamp(lo, hi);
+            if clamped != *val {
+                *val = clamped;
+                modified += 1;
+            }
+        }
+        if modified != 0 {
+            self.generations.bump(Region::Bits16);
+        }
+        modified
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RegionConfig;
+
+    #[test]
+    fn bits_from_addrs() {
+        let vt = ValueTable::new();
+        let addrs: Vec<u16> = [0xFFFF, 3, 64, 0x1234, 63, 3].into();
+        vt.set_bits(addrs);
+        assert_eq!(
+            vt.iter_set_bits().collect::<Vec<_>>(),
+            [3, 63, 64, 0x1234, 0xFFFF]
+        );
+        vt.clear_bits([63, 0xFFFF, 100]);
+        assert_eq!(vt.iter_set_bits().collect::<Vec<_>>(), [3, 64, 0x1234]);
+    }
+
+    #[test]
+    fn u16_strided() {
+        let vt = ValueTable::new();
+        assert_eq!(vt.set_u16_strided(0x0100, 4, &[1, 2, 3, 4]), Ok(4));
+        for a in 0x0100..0x0110 {
+            let expected = if (a - 0x0100) % 4 == 0 {
+                (a - 0x0100) / 4 + 1
+            } else {
+                0
+            };
+            assert_eq!(vt.get_u16(a), expected);
+        }
+        assert_eq!(vt.set_u16_strided(0xFFF8, 4, &[7, 8, 9]), Ok(2));
+        assert_eq!(vt.get_u16(0xFFF8), 7);
+        assert_eq!(vt.get_u16(0xFFFC), 8);
+        let small = ValueTable::with_regions(RegionConfig {
+            bits16: 4,
+            ..RegionConfig::empty()
+        });
+        assert_eq!(small.set_u16_strided(0x0000, 2, &[1, 2, 3, 4, 5]), Ok(2));
+        assert_eq!(small.get_u16s(0x0000, 4), &[1, 0, 2, 0]);
+        assert_eq!(
+            vt.set_u16_strided(0x0000, 0, &[1]),
+            Err(XvtError::ZeroStride)
+        );
+        assert_eq!(vt.get_u16(0x0000), 0);
+    }
+
+    #[test]
+    fn fill_pattern() {
+        let vt = ValueTable::new();
+        vt.fill_pattern_u8(Region::Bits8, &[0xDE, 0xAD, 0xBE]);
+        for a in u16::MIN..=u16::MAX {
+            assert_eq!(vt.get_u8(a), [0xDE, 0xAD, 0xBE][a as usize % 3]);
+        }
+        // 65536 = 3 * 21845 + 1，最后一次重复只保留首字节。
+        assert_eq!(vt.get_u8(0xFFFF), 0xDE);
+        assert_eq!(vt.get_u16(0x0000), 0);
+        assert!(!vt.get_bit(0x0000));
+
+        vt.fill_pattern_u8(Region::Bits16, &[0x12, 0x34]);
+        assert_eq!(vt.get_u16(0x1234), u16::from_ne_bytes([0x12, 0x34]));
+        assert_eq!(vt.get_u32(0x0000), 0);
+    }
+
+    #[test]
+    fn reinterpret() {
+        let vt = ValueTable::new();
+        vt.set_u8s(0x0010, &[0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88]);
+        assert_eq!(
+            vt.reinterpret_copy(Region::Bits8, Region::Bits32, 0x0010, 0x0002, 8),
+            Ok(())
+        );
+        assert_eq!(
+            vt.get_u32(0x0002),
+            u32::from_ne_bytes([0x11, 0x22, 0x33, 0x44])
+        );
+        assert_eq!(
+            vt.get_u32(0x0003),
+            u32::from_ne_bytes([0x55, 0x66, 0x77, 0x88])
+        );
+        assert_eq!(vt.get_u32(0x0004), 0);
+
+        assert_eq!(
+            vt.reinterpret_copy(Region::Bits8, Region::Bits32, 0xFFFC, 0x0000, 8),
+            Err(XvtError::OutOfRange)
+        );
+        assert_eq!(
+            vt.reinterpret_copy(Region::Bits8, Region::Bits16, 0x0000, 0xFFFF, 4),
+            Err(XvtError::OutOfRange)
+        );
+        assert_eq!(vt.get_u16(0xFFFF), 0);
+        for len in [usize::MAX, usize::MAX - 1] {
+            assert_eq!(
+                vt.reinterpret_copy(Region::Bits8, Region::Bits16, 0x0001, 0x0001, len),
+                Err(XvtError::OutOfRange)
+            );
+        }
+    }
+
+    #[test]
+    fn fill_u16() {
+        let vt = ValueTable::new();
+        vt.fill_u16(0xA55A);
+        for addr in [0x0000, 0x0001, 0x7FFF, 0xFFFE, 0xFFFF] {
+            assert_eq!(vt.get_u16(addr), 0xA55A);
+        }
+        assert_eq!(vt.get_u8(0xFFFF), 0);
+        assert_eq!(vt.get_u32(0x0000), 0);
+        vt.fill_f64(-0.5);
+        assert_eq!(vt.get_f64(0xFFFF), -0.5);
+    }
+
+    #[test]
+    fn clear_all() {
+        let mut vt = ValueTable::new();
+        vt.fill_pattern_u8(Region::Bit, &[0xA5]);
+        vt.fill_u8(1);
+        vt.fill_u16(2);
+        vt.fill_u32(3);
+        vt.fill_u64(4);
+        vt.clear_all();
+        for addr in [0x0000, 0x0001, 0x7FFF, 0xFFFF] {
+            assert!(!vt.get_bit(addr));
+            assert_eq!(vt.get_u8(addr), 0);
+            assert_eq!(vt.get_u16(addr), 0);
+            assert_eq!(vt.get_u32(addr), 0);
+            assert_eq!(vt.get_u64(addr), 0);
+        }
+        assert!(vt.as_bytes().iter().all(|b| *b == 0));
+        vt.set_u16(0x0001, 1);
+        vt.clear_all_mut();
+        assert_eq!(vt.get_u16(0x0001), 0);
+    }
+
+    #[test]
+    fn clear_region() {
+        let vt = ValueTable::new();
+        vt.fill_pattern_u8(Region::Bit, &[0xFF]);
+        vt.fill_u8(0xFF);
+        vt.fill_u16(0xFFFF);
+        vt.fill_u32(u32::MAX);
+        vt.clear_region(Region::Bits16);
+        assert!(vt.region_u16s(Region::Bits16).iter().all(|v| *v == 0));
+        assert_eq!(vt.get_u8(0xFFFF), 0xFF);
+        assert_eq!(vt.get_u32(0x0000), u32::MAX);
+        vt.clear_bits_all();
+        assert_eq!(vt.iter_set_bits().next(), None);
+        assert_eq!(vt.get_u8(0x0000), 0xFF);
+    }
+
+    #[test]
+    fn clamp_u16() {
+        let vt = ValueTable::new();
+        vt.set_u16s(0x0300, &[5, 10, 15, 20, 25, 30]);
+        vt.set_u16(0x0306, 1);
+        assert_eq!(vt.clamp_region_u16(0x0300, 6, 10, 20), 3);
+        assert_eq!(vt.get_u16s(0x0300, 7), &[10, 10, 15, 20, 20, 20, 1]);
+        assert_eq!(vt.clamp_region_u16(0x0300, 6, 10, 20), 0);
+
+        vt.set_u16(0xFFFF, 0xFFFF);
+        assert_eq!(vt.clamp_region_u16(0xFFFF, 100, 0, 0x7FFF), 1);
+        assert_eq!(vt.get_u16(0xFFFF), 0x7FFF);
+        assert_eq!(vt.clamp_region_u16(0xFFFF, 1, 10, 5), 0);
+        assert_eq!(vt.get_u16(0xFFFF), 0x7FFF);
+
+        let small = ValueTable::with_regions(RegionConfig {
+            bits16: 4,
+            ..RegionConfig::empty()
+        });
+        assert_eq!(small.clamp_region_u16(0x0100, 4, 0, 0), 0);
+    }
+
+    #[test]
+    fn shift_and_rotate_bits() {
+        let window = |vt: &ValueTable| vt.iter_set_bits().collect::<Vec<_>>();
+        let vt = ValueTable::new();
+        vt.set_bits([0x0100, 0x0101, 0x0105, 0x0107, 0x0108]);
+
+        vt.shift_bits(0x0100, 8, 2);
+        assert_eq!(window(&vt), [0x0102, 0x0103, 0x0107, 0x0108]);
+        vt.shift_bits(0x0100, 8, -3);
+        assert_eq!(window(&vt), [0x0100, 0x0104, 0x0108]);
+        vt.shift_bits(0x0100, 8, 8);
+        assert_eq!(window(&vt), [0x0108]);
+
+        vt.set_bits([0x0100, 0x0106]);
+        vt.rotate_bits(0x0100, 8, 3);
+        assert_eq!(window(&vt), [0x0101, 0x0103, 0x0108]);
+        vt.rotate_bits(0x0100, 8, -10);
+        assert_eq!(window(&vt), [0x0101, 0x0107, 0x0108]);
+        vt.rotate_bits(0x0100, 8, 16);
+        assert_eq!(window(&vt), [0x0101, 0x0107, 0x0108]);
+
+        vt.clear_bits_all();
+        vt.set_bits([0xFFFE, 0xFFFF]);
+        vt.rotate_bits(0xFFFE, 100, 1);
+        assert_eq!(window(&vt), [0xFFFE, 0xFFFF]);
+        vt.shift_bits(0xFFFE, 100, 1);
+        assert_eq!(window(&vt), [0xFFFF]);
+        vt.shift_bits(0x0000, 0, 1);
+    }
+
+    #[test]
+    fn shift_bits_marks_changes() {
+        let vt = ValueTable::new_tracked();
+        vt.set_bits([0x0010, 0x0011]);
+        vt.drain_dirty();
+        vt.shift_bits(0x0010, 4, 1);
+        assert_eq!(
+            vt.drain_dirty(),
+            [(Region::Bit, 0x0010), (Region::Bit, 0x0012)]
+        );
+    }
+}
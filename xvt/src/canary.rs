@@ -0,0 +1,116 @@
+use crate::region::RegionLayout;
+use crate::{CanaryError, Region, RegionConfig, ValueTable};
+
+/// 每个区域之后放置的哨兵字节数。
+const GUARD_LEN: usize = 64;
+
+/// 哨兵字节的取值。
+const CANARY: u8 = 0xA5;
+
+impl ValueTable {
+    /// 按区域配置 `config` 构建一个在每个区域之后放置哨兵字节的存储表，用于调试越界写入。
+    ///
+    /// 哨兵使各区域的偏移与 [`ValueTable::with_regions`] 构建的存储表不同，因此其快照只能
+    /// 恢复到同样带哨兵的存储表中。[`ValueTable::clear_all`] 会重新写入哨兵，
+    /// 用 `restore`、`read_from` 等方法整体覆盖缓冲区后则应立即调用 [`ValueTable::check_canaries`]。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xvt::{RegionConfig, ValueTable};
+    ///
+    /// let vt = ValueTable::with_regions_guarded(RegionConfig::full());
+    /// vt.set_u16(0xFFFF, 1);
+    /// assert_eq!(vt.check_canaries(), Ok(()));
+    /// ```
+    pub fn with_regions_guarded(config: RegionConfig) -> Self {
+        let layout = RegionLayout::guarded(config, GUARD_LEN);
+        let vt = match Self::try_with_layout(layout) {
+            Ok(vt) => vt,
+            Err(_) => alloc::alloc::handle_alloc_error(layout.alloc_layout()),
+        };
+        vt.seed_canaries();
+        vt
+    }
+
+    /// 区域 `region` 之后哨兵的字节偏移。
+    fn canary_offset(&self, region: Region) -> usize {
+        self.layout.offset(region) + self.layout.byte_len(region)
+    }
+
+    /// 在每个区域之后写入哨兵字节，未放置哨兵的存储表不做任何操作。
+    pub(crate) fn seed_canaries(&self) {
+        let guard = self.layout.guard();
+        if guard == 0 {
+            return;
+        }
+        for &region in Region::ALL {
+            let ofs = self.canary_offset(region);
+            unsafe { self.mem.as_ptr().add(ofs).write_bytes(CANARY, guard) };
+        }
+    }
+
+    /// 检查所有区域之后的哨兵是否完好，发现被改写的哨兵时返回第一个出错的位置。
+    ///
+    /// 未放置哨兵的存储表总是返回 `Ok(())`。
+    pub fn check_canaries(&self) -> Result<(), CanaryError> {
+        let guard = self.layout.guard();
+        if guard == 0 {
+            return Ok(());
+        }
+        let bytes = self.as_bytes();
+        for &region in Region::ALL {
+            let ofs = self.canary_offset(region);
+            if let Some(i) = bytes[ofs..ofs + guard].iter().position(|b| *b != CANARY) {
+                return Err(CanaryError {
+                    after: region,
+                    offset: i,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_overrun() {
+        let vt = ValueTable::with_regions_guarded(RegionConfig {
+            bits: 64,
+            bits16: 4,
+            bits32: 4,
+            ..RegionConfig::empty()
+        });
+        vt.set_u16s(0x0000, &[1, 2, 3, 4, 5]);
+        vt.set_u32(0x0003, u32::MAX);
+        vt.set_bit(63);
+        assert_eq!(vt.check_canaries(), Ok(()));
+        vt.clear_all();
+        assert_eq!(vt.check_canaries(), Ok(()));
+
+        // 模拟 16 位区域越界一个值的写入。
+        let (ptr, _) = unsafe { vt.as_raw_parts() };
+        let end = vt.region_offset(Region::Bits16) + vt.region_len(Region::Bits16);
+        unsafe { ptr.add(end + 1).write(0) };
+        assert_eq!(
+            vt.check_canaries(),
+            Err(CanaryError {
+                after: Region::Bits16,
+                offset: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn full_guarded() {
+        let vt = ValueTable::with_regions_guarded(RegionConfig::full());
+        assert_eq!(vt.as_bytes().len(), ValueTable::buffer_len());
+        vt.set_u128(0xFFFF, u128::MAX);
+        vt.set_u8(0xFFFF, u8::MAX);
+        assert_eq!(vt.check_canaries(), Ok(()));
+        assert_eq!(ValueTable::new().check_canaries(), Ok(()));
+    }
+}
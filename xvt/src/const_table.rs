@@ -0,0 +1,138 @@
+use crate::region::RegionLayout;
+use crate::{Backing, RegionConfig, ValueTable};
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ptr::NonNull;
+use core::sync::atomic::AtomicU64;
+
+/// 按 16 字节对齐的内联缓冲区，满足所有区域值类型的对齐要求。
+#[repr(C, align(16))]
+struct Storage<const N: usize>(UnsafeCell<[u8; N]>);
+
+/// 一个代表容量在编译期确定、缓冲区内联存放的多种值存储表的类型。
+///
+/// 缓冲区作为 `[u8; N]` 直接存放在值内部，可以放在栈上或 `static` 中，不依赖堆分配器。
+/// 区域布局由 [`RegionConfig`] 在常量上下文中计算，所需字节数超过 `N` 时编译失败，
+/// 可用 [`ConstValueTable::size_for`] 求出给定配置所需的 `N`。
+///
+/// 通过 [`ConstValueTable::table`] 获得可按 [`ValueTable`] 使用的视图。每个视图的回调、
+/// 脏标记及 [`ValueTable::write_consistent`] 的版本号相互独立，随视图的释放而丢弃。
+///
+/// # Examples
+///
+/// ```
+/// use xvt::{ConstValueTable, RegionConfig};
+///
+/// const CONFIG: RegionConfig = RegionConfig {
+///     bits: 64,
+///     bits16: 8,
+///     ..RegionConfig::empty()
+/// };
+/// static TABLE: ConstValueTable<{ ConstValueTable::<0>::size_for(CONFIG) }> =
+///     ConstValueTable::new(CONFIG);
+///
+/// let vt = TABLE.table();
+/// vt.set_bit(0x0003);
+/// vt.set_u16(0x0007, 0xBEEF);
+/// assert_eq!(TABLE.table().get_u16(0x0007), 0xBEEF);
+/// ```
+pub struct ConstValueTable<const N: usize> {
+    layout: RegionLayout,
+    buf: Storage<N>,
+}
+
+impl<const N: usize> ConstValueTable<N> {
+    /// 按区域配置 `config` 构建一个全零的存储表，可在常量上下文中使用。
+    ///
+    /// # Panics
+    ///
+    /// 配置所需的字节数超过 `N` 时触发 panic，在常量上下文中表现为编译错误。
+    pub const fn new(config: RegionConfig) -> Self {
+        let layout = RegionLayout::new(config);
+        assert!(layout.size() <= N, "buffer too small for region config");
+        Self {
+            layout,
+            buf: Storage(UnsafeCell::new([0; N])),
+        }
+    }
+
+    /// 区域配置 `config` 所需的缓冲区字节数。
+    pub const fn size_for(config: RegionConfig) -> usize {
+        RegionLayout::new(config).size()
+    }
+
+    /// 获取一个访问本存储表的视图。
+    pub fn table(&self) -> ConstValueTableRef<'_> {
+        let mem = unsafe { NonNull::new_unchecked(self.buf.0.get().cast::<u8>()) };
+        ConstValueTableRef {
+            vt: ValueTable {
+                mem,
+                layout: self.layout,
+                observers: Default::default(),
+                dirty: None,
+                presence: None,
+                version: AtomicU64::new(0),
+                generations: Default::default(),
+                backing: Backing::Borrowed,
+                zeroize: false,
+            },
+            _owner: PhantomData,
+        }
+    }
+}
+
+unsafe impl<const N: usize> Send for ConstValueTable<N> {}
+unsafe impl<const N: usize> Sync for ConstValueTable<N> {}
+
+/// 由 [`ConstValueTable::table`] 返回的存储表视图。
+pub struct ConstValueTableRef<'a> {
+    vt: ValueTable,
+    _owner: PhantomData<&'a ()>,
+}
+
+impl Deref for ConstValueTableRef<'_> {
+    type Target = ValueTable;
+
+    fn deref(&self) -> &ValueTable {
+        &self.vt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Region;
+
+    const CONFIG: RegionConfig = RegionConfig {
+        bits: 64,
+        bits16: 4,
+        ..RegionConfig::empty()
+    };
+
+    #[test]
+    fn bits_and_u16() {
+        let table = ConstValueTable::<{ ConstValueTable::<0>::size_for(CONFIG) }>::new(CONFIG);
+        let vt = table.table();
+        vt.set_bit(0x0003);
+        vt.set_u16(0x0003, 0xBEEF);
+        vt.set_u16(0x0004, 1);
+        drop(vt);
+
+        let vt = table.table();
+        assert!(vt.get_bit(0x0003));
+        assert!(!vt.get_bit(0x0040));
+        assert_eq!(vt.get_u16s(0x0000, 4), &[0, 0, 0, 0xBEEF]);
+        assert_eq!(vt.get_u16(0x0004), 0);
+        assert_eq!(vt.region_capacity(Region::Bits16), 4);
+        assert_eq!(vt.get_u32(0x0000), 0);
+    }
+
+    #[test]
+    fn static_table() {
+        static TABLE: ConstValueTable<64> = ConstValueTable::new(CONFIG);
+        TABLE.table().set_u16(0x0001, 7);
+        let copy = TABLE.table().clone();
+        assert_eq!(copy.get_u16(0x0001), 7);
+    }
+}
@@ -0,0 +1,327 @@
+use crate::{LoadError, Region, ValueTable};
+use core::num::IntErrorKind;
+use paste::paste;
+use std::io::{self, BufRead};
+
+/// 按字段类型解析出的一条记录的值。
+macro_rules! define_value {
+    ($($t:ident),*) => {
+        paste! {
+            #[derive(Debug, Clone, Copy)]
+            enum Value {
+                Bit(bool),
+                $([<$t:camel>]($t),)*
+            }
+
+            impl Value {
+                /// 按类型名 `ty` 解析值文本 `text`。
+                fn parse(ty: &str, text: &str, line: usize) -> Result<Self, LoadError> {
+                    match ty {
+                        "bit" => match text {
+                            "0" | "false" => Ok(Value::Bit(false)),
+                            "1" | "true" => Ok(Value::Bit(true)),
+                            _ => Err(LoadError::Syntax { line }),
+                        },
+                        $(stringify!($t) => $t::parse_field(text, line).map(Value::[<$t:camel>]),)*
+                        _ => Err(LoadError::UnknownType { line }),
+                    }
+                }
+
+                fn apply(self, vt: &ValueTable, addr: u16) {
+                    match self {
+                        Value::Bit(true) => vt.set_bit(addr),
+                        Value::Bit(false) => vt.clear_bit(addr),
+                        $(Value::[<$t:camel>](v) => vt.[<set_ $t>](addr, v),)*
+                    }
+                }
+            }
+        }
+    };
+}
+
+define_value!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, f32, f64);
+
+/// 从文本解析一个字段值，整数支持十进制及 `0x` 前缀的十六进制。
+trait ParseField: Sized {
+    fn parse_field(text: &str, line: usize) -> Result<Self, LoadError>;
+}
+
+macro_rules! impl_parse_int {
+    ($($t:ty),*) => {
+        $(impl ParseField for $t {
+            fn parse_field(text: &str, line: usize) -> Result<Self, LoadError> {
+                let (sign, body) = match text.strip_prefix('-') {
+                    Some(rest) => ("-", rest),
+                    None => ("", text),
+                };
+                let parsed = match body.strip_prefix("0x").or_else(|| body.strip_prefix("0X")) {
+                    Some(hex) => <$t>::from_str_radix(&format!("{sign}{hex}"), 16),
+                    None => text.parse(),
+                };
+                parsed.map_err(|e| match e.kind() {
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                        LoadError::OutOfRange { line }
+                    }
+                    // 无符号类型不接受负号，但负数本身是合法的数值。
+                    IntErrorKind::InvalidDigit
+                        if <$t>::MIN == 0 && !sign.is_empty() && body.parse::<u128>().is_ok() =>
+                    {
+                        LoadError::OutOfRange { line }
+                    }
+                    _ => LoadError::Syntax { line },
+                })
+            }
+        })*
+    };
+}
+
+impl_parse_int!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+
+macro_rules! impl_parse_float {
+    ($($t:ty),*) => {
+        $(impl ParseField for $t {
+            fn parse_field(text: &str, line: usize) -> Result<Self, LoadError> {
+                let v: $t = text.parse().map_err(|_| LoadError::Syntax { line })?;
+                // 有限的文本解析为无穷大说明超出了该类型的表示范围。
+                if v.is_infinite() && !text.trim_start_matches(['-', '+']).starts_with(['i', 'I']) {
+                    return Err(LoadError::OutOfRange { line });
+                }
+                Ok(v)
+            }
+        })*
+    };
+}
+
+impl_parse_float!(f32, f64);
+
+/// 一个代表 [`ValueTable::dump_to_csv_with`] 输出选项的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// 是否输出 `address,type,value` 表头。
+    pub header: bool,
+    /// 是否输出值为零的条目。
+    pub all: bool,
+}
+
+impl Default for CsvOptions {
+    /// 输出表头，只输出非零条目。
+    fn default() -> Self {
+        Self {
+            header: true,
+            all: false,
+        }
+    }
+}
+
+impl ValueTable {
+    /// 以 `地址,类型,值` 格式将区域 `regions` 中的非零值写入 `w`，首行为表头。
+    ///
+    /// 等同于以默认的 [`CsvOptions`] 调用 [`ValueTable::dump_to_csv_with`]。
+    pub fn dump_to_csv<W: io::Write>(&self, w: &mut W, regions: &[Region]) -> io::Result<()> {
+        self.dump_to_csv_with(w, regions, CsvOptions::default())
+    }
+
+    /// 以 `地址,类型,值` 格式将区域 `regions` 中的值写入 `w`，输出可由 [`ValueTable::load_from_csv`] 读回。
+    ///
+    /// 各区域按给定顺序、地址升序输出。地址以 `0x` 前缀的十六进制书写；数值区域的值按同宽度的
+    /// 无符号整数（`u8` ~ `u128`）以十进制书写，浮点值输出其原始位，因此读回后与原值逐位相同。
+    pub fn dump_to_csv_with<W: io::Write>(
+        &self,
+        w: &mut W,
+        regions: &[Region],
+        options: CsvOptions,
+    ) -> io::Result<()> {
+        if options.header {
+            writeln!(w, "address,type,value")?;
+        }
+        for &region in regions {
+            if region == Region::Bit {
+                if options.all {
+                    for addr in 0..self.region_capacity(region) {
+                        writeln!(w, "0x{:04X},bit,{}", addr, self.get_bit(addr as u16) as u8)?;
+                    }
+                } else {
+                    for addr in self.iter_set_bits() {
+                        writeln!(w, "0x{:04X},bit,1", addr)?;
+                    }
+                }
+                continue;
+            }
+            let n = region.element_size();
+            let bytes = self.region_bytes(region);
+            for (addr, v) in bytes.chunks_exact(n).enumerate() {
+                if !options.all && v.iter().all(|b| *b == 0) {
+                    continue;
+                }
+                let mut raw = [0u8; 16];
+                raw[..n].copy_from_slice(v);
+                let val = u128::from_ne_bytes(raw);
+                let val = if cfg!(target_endian = "big") {
+                    val >> (128 - n * 8)
+                } else {
+                    val
+                };
+                writeln!(w, "0x{:04X},u{},{}", addr, n * 8, val)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 从 `r` 读取以逗号或制表符分隔的 `地址,类型,值` 记录并写入存储表，返回写入的记录数。
+    ///
+    /// 地址与整数值支持十进制及 `0x` 前缀的十六进制；类型为 `bit`、`u8` ~ `u128`、`i8` ~ `i128`、
+    /// `f32` 或 `f64`，单比特值为 `0`、`1`、`false` 或 `true`。字段两侧的空白及空行被忽略，
+    /// 首行的地址无法解析时视为表头跳过。
+    ///
+    /// 所有记录解析成功后才开始写入，任一行出错时返回带行号（从 1 开始）的 [`LoadError`]，
+    /// 存储表保持不变。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xvt::ValueTable;
+    ///
+    /// let vt = ValueTable::new();
+    /// let csv = "address,type,value\n0x0010,u16,0xBEEF\n3,bit,1\n";
+    /// assert_eq!(vt.load_from_csv(csv.as_bytes()).unwrap(), 2);
+    /// assert_eq!(vt.get_u16(0x0010), 0xBEEF);
+    /// assert!(vt.get_bit(3));
+    /// ```
+    pub fn load_from_csv<R: io::Read>(&self, r: R) -> Result<usize, LoadError> {
+        let mut records = Vec::new();
+        for (i, text) in io::BufReader::new(r).lines().enumerate() {
+            let line = i + 1;
+            let text = text.map_err(LoadError::Io)?;
+            if text.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = text.split([',', '\t']).map(str::trim).collect();
+            let [addr, ty, val] = fields[..] else {
+                return Err(LoadError::Syntax { line });
+            };
+            let addr = match u16::parse_field(addr, line) {
+                Ok(addr) => addr,
+                Err(LoadError::Syntax { .. }) if line == 1 => continue,
+                Err(e) => return Err(e),
+            };
+            records.push((addr, Value::parse(&ty.to_ascii_lowercase(), val, line)?));
+        }
+        for (addr, val) in &records {
+            val.apply(self, *addr);
+        }
+        Ok(records.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_records() {
+        let vt = ValueTable::new();
+        let csv = "address,type,value
+0x0010,u16,0xBEEF
+
+7,bit,true
+8\tbit\t0
+0x0001, i16, -2
+0x0002,u8,255
+0x0003,i8,-0x80
+0xFFFF,f32,1.5
+0x0004,U128,340282366920938463463374607431768211455
+";
+        vt.set_bit(8);
+        assert_eq!(vt.load_from_csv(csv.as_bytes()).unwrap(), 8);
+        assert_eq!(vt.get_u16(0x0010), 0xBEEF);
+        assert!(vt.get_bit(7));
+        assert!(!vt.get_bit(8));
+        assert_eq!(vt.get_i16(0x0001), -2);
+        assert_eq!(vt.get_u8(0x0002), 255);
+        assert_eq!(vt.get_i8(0x0003), i8::MIN);
+        assert_eq!(vt.get_f32(0xFFFF), 1.5);
+        assert_eq!(vt.get_u128(0x0004), u128::MAX);
+    }
+
+    #[test]
+    fn reports_line() {
+        let vt = ValueTable::new();
+        let err = |csv: &str| vt.load_from_csv(csv.as_bytes()).unwrap_err();
+        assert!(matches!(
+            err("1,u8,1\n2,u8,256\n"),
+            LoadError::OutOfRange { line: 2 }
+        ));
+        assert!(matches!(
+            err("1,u8,-1\n"),
+            LoadError::OutOfRange { line: 1 }
+        ));
+        assert!(matches!(err("1,u8,--1\n"), LoadError::Syntax { line: 1 }));
+        assert!(matches!(
+            err("a,b,c\n1,u24,1\n"),
+            LoadError::UnknownType { line: 2 }
+        ));
+        assert!(matches!(err("1,u8\n"), LoadError::Syntax { line: 1 }));
+        assert!(matches!(
+            err("1,u8,1\nx,u8,1\n"),
+            LoadError::Syntax { line: 2 }
+        ));
+        assert!(matches!(
+            err("0x10000,u8,1\n"),
+            LoadError::OutOfRange { line: 1 }
+        ));
+        assert!(matches!(
+            err("1,f32,1e39\n"),
+            LoadError::OutOfRange { line: 1 }
+        ));
+        assert_eq!(vt.get_u8(1), 0);
+    }
+
+    #[test]
+    fn dump_round_trip() {
+        let vt = ValueTable::new();
+        vt.set_bit(0x0003);
+        vt.set_bit(0xFFFF);
+        vt.set_i8(0x0001, -1);
+        vt.set_u16(0x1234, 0xBEEF);
+        vt.set_f32(0x0002, -1.5);
+        vt.set_f64(0xFFFF, f64::MIN_POSITIVE);
+        vt.set_i128(0x0000, i128::MIN);
+
+        let mut out = Vec::new();
+        vt.dump_to_csv(&mut out, Region::ALL).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 8);
+        assert!(text.contains("0x1234,u16,48879\n"));
+
+        let copy = ValueTable::new();
+        assert_eq!(copy.load_from_csv(text.as_bytes()).unwrap(), 7);
+        assert!(copy.as_bytes() == vt.as_bytes());
+    }
+
+    #[test]
+    fn dump_all() {
+        let vt = ValueTable::with_regions(crate::RegionConfig {
+            bits: 64,
+            bits16: 2,
+            ..crate::RegionConfig::empty()
+        });
+        vt.set_u16(0x0001, 7);
+        let mut out = Vec::new();
+        let options = CsvOptions {
+            header: false,
+            all: true,
+        };
+        vt.dump_to_csv_with(&mut out, &[Region::Bits16], options)
+            .unwrap();
+        assert_eq!(out, b"0x0000,u16,0\n0x0001,u16,7\n");
+        out.clear();
+        vt.dump_to_csv_with(&mut out, &[Region::Bit], options)
+            .unwrap();
+        assert_eq!(out.split(|b| *b == b'\n').count(), 65);
+
+        let empty = ValueTable::with_regions(crate::RegionConfig::empty());
+        out.clear();
+        empty.dump_to_csv(&mut out, &[Region::Bit]).unwrap();
+        assert_eq!(out.split(|b| *b == b'\n').count(), 2);
+    }
+}
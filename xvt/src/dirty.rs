@@ -0,0 +1,121 @@
+use crate::{Region, ValueTable};
+use alloc::{boxed::Box, vec::Vec};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// 每个区域在脏位图中占用的字数，每个区域都覆盖完整的 `u16` 地址空间。
+const REGION_WORDS: usize = 65536 / usize::BITS as usize;
+
+/// 标记每个被写入地址的脏位图，按区域依次排列。
+pub(crate) struct DirtyMap {
+    words: Box<[AtomicUsize]>,
+}
+
+impl DirtyMap {
+    pub(crate) fn new() -> Self {
+        Self {
+            words: (0..REGION_WORDS * Region::COUNT)
+                .map(|_| AtomicUsize::new(0))
+                .collect(),
+        }
+    }
+
+    /// 将区域 `region` 中的地址 `addr` 标记为脏。
+    #[inline]
+    pub(crate) fn mark(&self, region: Region, addr: u16) {
+        let (offset, mask) = ValueTable::bit_location(addr);
+        self.words[region as usize * REGION_WORDS + offset].fetch_or(mask, Ordering::Relaxed);
+    }
+
+    /// 区域 `region` 中的地址 `addr` 是否已被标记。
+    #[inline]
+    pub(crate) fn is_marked(&self, region: Region, addr: u16) -> bool {
+        let (offset, mask) = ValueTable::bit_location(addr);
+        self.words[region as usize * REGION_WORDS + offset].load(Ordering::Relaxed) & mask != 0
+    }
+
+    /// 复制出一个标记相同的位图。
+    pub(crate) fn copy(&self) -> Self {
+        Self {
+            words: self
+                .words
+                .iter()
+                .map(|w| AtomicUsize::new(w.load(Ordering::Relaxed)))
+                .collect(),
+        }
+    }
+}
+
+impl ValueTable {
+    /// 构建一个开启脏标记跟踪的多种值存储表实例。
+    ///
+    /// 每次通过 `set_*` 系列、`set_bit` 或 `clear_bit` 写入都会将对应地址标记为脏，
+    /// 无论值是否发生变化，可通过 [`ValueTable::drain_dirty`] 取出。
+    pub fn new_tracked() -> Self {
+        let mut vt = Self::new();
+        vt.dirty = Some(DirtyMap::new());
+        vt
+    }
+
+    /// 取出自上次调用以来被写入过的所有地址并清除脏标记，按区域及地址升序排列。
+    ///
+    /// 存储表未开启脏标记跟踪时总是返回空列表。
+    pub fn drain_dirty(&self) -> Vec<(Region, u16)> {
+        let Some(dirty) = &self.dirty else {
+            return Vec::new();
+        };
+        let mut out = Vec::new();
+        for (i, word) in dirty.words.iter().enumerate() {
+            let mut x = word.swap(0, Ordering::Relaxed);
+            let region = Region::from_id((i / REGION_WORDS) as u8).unwrap();
+            let base = (i % REGION_WORDS) * usize::BITS as usize;
+            while x != 0 {
+                out.push((region, (base + x.trailing_zeros() as usize) as u16));
+                x &= x - 1;
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drain_dirty() {
+        let vt = ValueTable::new_tracked();
+        vt.set_u16(0x0100, 1);
+        vt.set_u16(0x0100, 1);
+        vt.set_bit(0xFFFF);
+        vt.clear_bit(0x0000);
+        vt.set_u64s(0xFFFE, &[1, 2, 3]);
+        vt.set_u8(0x0040, 0);
+        assert_eq!(
+            vt.drain_dirty(),
+            [
+                (Region::Bit, 0x0000),
+                (Region::Bit, 0xFFFF),
+                (Region::Bits8, 0x0040),
+                (Region::Bits16, 0x0100),
+                (Region::Bits64, 0xFFFE),
+                (Region::Bits64, 0xFFFF),
+            ]
+        );
+        assert!(vt.drain_dirty().is_empty());
+        vt.set_f32(0x0002, 1.0);
+        assert_eq!(vt.drain_dirty(), [(Region::Bits32, 0x0002)]);
+    }
+
+    #[test]
+    fn untracked() {
+        let vt = ValueTable::new();
+        vt.set_u16(0x0100, 1);
+        assert!(vt.drain_dirty().is_empty());
+        let vt = ValueTable::new_tracked();
+        vt.set_u16(0x0100, 1);
+        let copy = vt.clone();
+        copy.set_u16(0x0200, 1);
+        assert_eq!(copy.drain_dirty(), [(Region::Bits16, 0x0200)]);
+        assert_eq!(vt.drain_dirty(), [(Region::Bits16, 0x0100)]);
+    }
+}
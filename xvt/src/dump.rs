@@ -0,0 +1,100 @@
+use crate::{Region, ValueTable};
+use alloc::string::String;
+use core::fmt::Write;
+
+impl ValueTable {
+    /// 以十六进制列表的形式输出区域 `region` 中从 `start` 开始的 `count` 个值，便于调试时查看实际内容。
+    ///
+    /// 每行以四位十六进制地址开头，随后是从该地址起的若干个值，每行最多覆盖 16 个字节；
+    /// 单比特区域每行 16 个值，以 `0`/`1` 表示。值按元素宽度补齐，例如 8 位值占 2 位、
+    /// 16 位值占 4 位。超出区域容量的部分被忽略。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xvt::{Region, ValueTable};
+    ///
+    /// let vt = ValueTable::new();
+    /// vt.set_u16(0x0011, 0xBEEF);
+    /// assert_eq!(vt.dump_region_hex(Region::Bits16, 0x0010, 3), "0010: 0000 BEEF 0000\n");
+    /// ```
+    pub fn dump_region_hex(&self, region: Region, start: u16, count: u16) -> String {
+        let end = (start as usize + count as usize).min(self.region_capacity(region));
+        let (digits, per_line) = match region {
+            Region::Bit => (1, 16),
+            _ => (region.element_size() * 2, 16 / region.element_size()),
+        };
+        let mut out = String::new();
+        for (i, addr) in (start as usize..end).enumerate() {
+            let addr = addr as u16;
+            if i % per_line == 0 {
+                if i != 0 {
+                    out.push('\n');
+                }
+                let _ = write!(out, "{:04X}:", addr);
+            }
+            let val = match region {
+                Region::Bit => self.get_bit(addr) as u128,
+                Region::Bits8 => self.get_u8(addr) as u128,
+                Region::Bits16 => self.get_u16(addr) as u128,
+                Region::Bits32 => self.get_u32(addr) as u128,
+                Region::Bits64 => self.get_u64(addr) as u128,
+                Region::Bits128 => self.get_u128(addr),
+            };
+            let _ = write!(out, " {:0width$X}", val, width = digits);
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RegionConfig;
+
+    #[test]
+    fn dump_region_hex() {
+        let vt = ValueTable::new();
+        vt.set_u8(0x0021, 0x7F);
+        vt.set_u16(0x0100, 0x1234);
+        vt.set_u16(0x0109, 0xABCD);
+        vt.set_u32(0x0002, 0xDEAD_BEEF);
+        vt.set_u128(0x0000, 1);
+        vt.set_bit(0x0003);
+
+        assert_eq!(
+            vt.dump_region_hex(Region::Bits16, 0x0100, 10),
+            "0100: 1234 0000 0000 0000 0000 0000 0000 0000\n0108: 0000 ABCD\n"
+        );
+        assert_eq!(
+            vt.dump_region_hex(Region::Bits8, 0x0020, 3),
+            "0020: 00 7F 00\n"
+        );
+        assert_eq!(
+            vt.dump_region_hex(Region::Bits32, 0x0001, 2),
+            "0001: 00000000 DEADBEEF\n"
+        );
+        assert_eq!(
+            vt.dump_region_hex(Region::Bits128, 0x0000, 2),
+            "0000: 00000000000000000000000000000001\n0001: 00000000000000000000000000000000\n"
+        );
+        assert_eq!(
+            vt.dump_region_hex(Region::Bit, 0x0000, 5),
+            "0000: 0 0 0 1 0\n"
+        );
+        assert_eq!(vt.dump_region_hex(Region::Bits16, 0x0000, 0), "");
+
+        let small = ValueTable::with_regions(RegionConfig {
+            bits16: 2,
+            ..RegionConfig::empty()
+        });
+        small.set_u16(0x0001, 0x00FF);
+        assert_eq!(
+            small.dump_region_hex(Region::Bits16, 0x0000, 8),
+            "0000: 0000 00FF\n"
+        );
+    }
+}
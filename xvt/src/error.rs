@@ -0,0 +1,142 @@
+use crate::Region;
+use core::fmt;
+
+/// 一个代表值存储表操作错误的类型。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XvtError {
+    /// 补丁数据格式错误。
+    MalformedPatch,
+    /// 地址或长度超出区域范围。
+    OutOfRange,
+    /// 数据长度与期望的长度不符。
+    LengthMismatch {
+        /// 期望的字节数。
+        expected: usize,
+        /// 实际的字节数。
+        actual: usize,
+    },
+    /// 步长为零。
+    ZeroStride,
+}
+
+impl fmt::Display for XvtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XvtError::MalformedPatch => write!(f, "补丁数据格式错误"),
+            XvtError::OutOfRange => write!(f, "地址或长度超出区域范围"),
+            XvtError::LengthMismatch { expected, actual } => {
+                write!(f, "数据长度 {} 与期望长度 {} 不符", actual, expected)
+            }
+            XvtError::ZeroStride => write!(f, "步长不能为零"),
+        }
+    }
+}
+
+impl core::error::Error for XvtError {}
+
+/// 一个代表存储表布局校验失败的类型，由 [`ValueTable::validate_layout`](crate::ValueTable::validate_layout) 返回。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutError {
+    /// 两个区域的字节范围重叠。
+    Overlap(Region, Region),
+    /// 区域超出了缓冲区末尾。
+    OutOfBounds(Region),
+    /// 区域的起始偏移不满足其值类型的对齐要求。
+    Misaligned(Region),
+    /// 区域的偏移常量与按配置计算出的布局不一致。
+    Mismatch(Region),
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::Overlap(a, b) => write!(f, "区域 {:?} 与 {:?} 重叠", a, b),
+            LayoutError::OutOfBounds(r) => write!(f, "区域 {:?} 超出缓冲区", r),
+            LayoutError::Misaligned(r) => write!(f, "区域 {:?} 未对齐", r),
+            LayoutError::Mismatch(r) => write!(f, "区域 {:?} 的偏移常量与布局不一致", r),
+        }
+    }
+}
+
+impl core::error::Error for LayoutError {}
+
+/// 一个代表从文本导入寄存器值失败的类型，由
+/// [`ValueTable::load_from_csv`](crate::ValueTable::load_from_csv) 返回。
+///
+/// 行号从 1 开始计数。
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum LoadError {
+    /// 读取输入时发生 I/O 错误。
+    Io(std::io::Error),
+    /// 字段个数不对，或地址、值无法解析。
+    Syntax {
+        /// 出错的行号。
+        line: usize,
+    },
+    /// 不支持的值类型。
+    UnknownType {
+        /// 出错的行号。
+        line: usize,
+    },
+    /// 地址或值超出了对应类型的范围。
+    OutOfRange {
+        /// 出错的行号。
+        line: usize,
+    },
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "读取失败：{}", e),
+            LoadError::Syntax { line } => write!(f, "第 {} 行格式错误", line),
+            LoadError::UnknownType { line } => write!(f, "第 {} 行的值类型不受支持", line),
+            LoadError::OutOfRange { line } => write!(f, "第 {} 行的值超出类型范围", line),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// 一个代表哨兵被改写的类型，由 [`ValueTable::check_canaries`](crate::ValueTable::check_canaries) 返回。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanaryError {
+    /// 被改写的哨兵紧随其后的区域。
+    pub after: Region,
+    /// 第一个被改写的字节在哨兵中的偏移。
+    pub offset: usize,
+}
+
+impl fmt::Display for CanaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "区域 {:?} 之后的哨兵在偏移 {} 处被改写",
+            self.after, self.offset
+        )
+    }
+}
+
+impl core::error::Error for CanaryError {}
+
+/// 一个代表存储表内存分配失败的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "存储表内存分配失败")
+    }
+}
+
+impl core::error::Error for AllocError {}
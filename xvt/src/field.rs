@@ -0,0 +1,80 @@
+use crate::ValueTable;
+
+/// 计算位于第 `shift` 位起、宽 `width` 位的子字段掩码。
+///
+/// # Panics
+///
+/// `shift + width` 超过 16 时触发 panic。
+fn field_mask(shift: u8, width: u8) -> u16 {
+    assert!(
+        shift as u32 + width as u32 <= 16,
+        "bit field {shift}+{width} exceeds 16 bits"
+    );
+    (((1u32 << width) - 1) << shift) as u16
+}
+
+impl ValueTable {
+    /// 获取指定地址 `addr` 类型为 `u16` 的值中，从第 `shift` 位起、宽 `width` 位的子字段。
+    ///
+    /// # Panics
+    ///
+    /// `shift + width` 超过 16 时触发 panic。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xvt::ValueTable;
+    ///
+    /// let vt = ValueTable::new();
+    /// vt.set_u16(0x0001, 0xABCD);
+    /// assert_eq!(vt.get_bitfield(0x0001, 4, 8), 0xBC);
+    /// ```
+    pub fn get_bitfield(&self, addr: u16, shift: u8, width: u8) -> u16 {
+        let mask = field_mask(shift, width);
+        ((self.get_u16(addr) & mask) as u32 >> shift) as u16
+    }
+
+    /// 将指定地址 `addr` 类型为 `u16` 的值中，从第 `shift` 位起、宽 `width` 位的子字段设为 `value`。
+    ///
+    /// `value` 超出 `width` 位的部分被截去，寄存器中的其他位保持不变。读取与写回不是原子操作，
+    /// 并发修改同一寄存器中的其他子字段时需要外部同步。
+    ///
+    /// # Panics
+    ///
+    /// `shift + width` 超过 16 时触发 panic。
+    pub fn set_bitfield(&self, addr: u16, shift: u8, width: u8, value: u16) {
+        let mask = field_mask(shift, width);
+        let old = self.get_u16(addr);
+        let new = ((value as u32) << shift) as u16 & mask;
+        self.set_u16(addr, (old & !mask) | new);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packed_fields() {
+        let vt = ValueTable::new();
+        vt.set_bitfield(0x0010, 0, 4, 0x5);
+        vt.set_bitfield(0x0010, 4, 4, 0xA);
+        vt.set_bitfield(0x0010, 8, 8, 0xC3);
+        assert_eq!(vt.get_u16(0x0010), 0xC3A5);
+        assert_eq!(vt.get_bitfield(0x0010, 0, 4), 0x5);
+        assert_eq!(vt.get_bitfield(0x0010, 4, 4), 0xA);
+        assert_eq!(vt.get_bitfield(0x0010, 8, 8), 0xC3);
+
+        vt.set_bitfield(0x0010, 4, 4, 0x1F);
+        assert_eq!(vt.get_u16(0x0010), 0xC3F5);
+        vt.set_bitfield(0x0010, 0, 16, 0x1234);
+        assert_eq!(vt.get_bitfield(0x0010, 0, 16), 0x1234);
+        assert_eq!(vt.get_bitfield(0x0010, 16, 0), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn field_too_wide() {
+        ValueTable::new().set_bitfield(0x0000, 12, 8, 0);
+    }
+}
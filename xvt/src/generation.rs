@@ -0,0 +1,143 @@
+use crate::{Region, ValueTable};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// 各区域的变化代数计数器。
+///
+/// 首次调用 [`ValueTable::region_generation`] 后才开始计数，未使用时写入路径不受影响。
+#[derive(Default)]
+pub(crate) struct Generations {
+    active: AtomicBool,
+    counts: [AtomicU32; Region::COUNT],
+}
+
+impl Generations {
+    #[inline]
+    pub(crate) fn active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// 区域 `region` 中的值发生变化后调用。
+    #[inline]
+    pub(crate) fn bump(&self, region: Region) {
+        if self.active() {
+            self.counts[region as usize].fetch_add(1, Ordering::Release);
+        }
+    }
+
+    /// 将所有区域的代数加一，用于整体替换缓冲区之后。
+    pub(crate) fn bump_all(&self) {
+        for &region in Region::ALL {
+            self.bump(region);
+        }
+    }
+}
+
+impl ValueTable {
+    /// 获取区域 `region` 的变化代数。
+    ///
+    /// 每当 `set_*` 系列、`set_bit`、`clear_bit` 或原子操作实际改变了该区域中的某个值，代数加一；写入相同的值
+    /// 不会改变代数，计数溢出时回绕。读取方可以缓存代数，仅在其变化时重新读取整个区域。
+    ///
+    /// 首次调用本方法后才开始计数。`fill_*`、`clear_all`、`restore`、`apply_patch` 等批量操作每次调用使
+    /// 涉及的区域的代数加一，不逐个比较值是否变化；[`ValueTable::swap_buffers`] 使所有区域的代数加一。
+    /// 获取可变区域切片（如 [`ValueTable::region_mut_u16`]）时也视为该区域发生了变化。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xvt::{Region, ValueTable};
+    ///
+    /// let vt = ValueTable::new();
+    /// let seen = vt.region_generation(Region::Bits16);
+    /// vt.set_u16(0x0001, 7);
+    /// assert_ne!(vt.region_generation(Region::Bits16), seen);
+    /// ```
+    pub fn region_generation(&self, region: Region) -> u32 {
+        self.generations.active.store(true, Ordering::Relaxed);
+        self.generations.counts[region as usize].load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bumps_on_change() {
+        let vt = ValueTable::new();
+        vt.set_u16(0x0001, 1);
+        let g16 = vt.region_generation(Region::Bits16);
+        let g32 = vt.region_generation(Region::Bits32);
+        let gbit = vt.region_generation(Region::Bit);
+
+        vt.set_u16(0x0001, 1);
+        assert_eq!(vt.region_generation(Region::Bits16), g16);
+        vt.set_u16(0x0001, 2);
+        assert_eq!(vt.region_generation(Region::Bits16), g16 + 1);
+        vt.set_u16s(0x0001, &[2, 3, 4]);
+        assert_eq!(vt.region_generation(Region::Bits16), g16 + 3);
+        assert_eq!(vt.region_generation(Region::Bits32), g32);
+
+        vt.clear_bit(0x0005);
+        assert_eq!(vt.region_generation(Region::Bit), gbit);
+        vt.set_bit(0x0005);
+        assert_eq!(vt.region_generation(Region::Bit), gbit + 1);
+    }
+
+    #[test]
+    fn swap_buffers_bumps_all() {
+        let mut vt = ValueTable::new();
+        let g = vt.region_generation(Region::Bits64);
+        let mut other = ValueTable::new();
+        vt.swap_buffers(&mut other);
+        assert_eq!(vt.region_generation(Region::Bits64), g + 1);
+    }
+
+    #[test]
+    fn bulk_and_atomic_bump() {
+        let vt = ValueTable::new();
+        let gen = |r| vt.region_generation(r);
+        let g16 = gen(Region::Bits16);
+        vt.set_u16(0x0000, 100);
+        vt.clamp_region_u16(0x0000, 1, 0, 10);
+        assert_eq!(gen(Region::Bits16), g16 + 2);
+        vt.fill_u16(7);
+        vt.mask_write_u16(0x0000, 0x00FF, 0x0100);
+        vt.rotate_left_u16(0x0000, 4);
+        assert_eq!(gen(Region::Bits16), g16 + 5);
+
+        let g32 = gen(Region::Bits32);
+        vt.add_u32(0x0010, 1);
+        vt.saturating_add_u32(0x0010, 1);
+        vt.fetch_add_u32(0x0010, 0);
+        assert_eq!(vt.compare_exchange_u32(0x0010, 2, 3), Ok(2));
+        assert_eq!(gen(Region::Bits32), g32 + 3);
+
+        let gbit = gen(Region::Bit);
+        vt.set_bit_atomic(0x0001);
+        vt.set_bit_atomic(0x0001);
+        vt.toggle_bit_atomic(0x0002);
+        assert_eq!(vt.ack_alarms(&[0x0001, 0x0002]), 2);
+        assert_eq!(gen(Region::Bit), gbit + 4);
+
+        let g8 = gen(Region::Bits8);
+        let snap = vt.snapshot();
+        vt.restore(&snap).unwrap();
+        vt.clear_region(Region::Bits8);
+        vt.fill_pattern_u8(Region::Bits8, &[1, 2]);
+        vt.reinterpret_copy(Region::Bits16, Region::Bits8, 0, 0, 2)
+            .unwrap();
+        assert_eq!(gen(Region::Bits8), g8 + 4);
+
+        let g64 = gen(Region::Bits64);
+        let patch = {
+            let other = vt.clone();
+            other.set_u64(0x0003, 9);
+            other.encode_patch(vt.as_bytes())
+        };
+        vt.apply_patch(&patch).unwrap();
+        assert_eq!(gen(Region::Bits64), g64 + 1);
+        vt.clear_all();
+        assert_eq!(gen(Region::Bits64), g64 + 2);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{Region, ValueTable};
+use core::hash::{Hash, Hasher};
+
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+/// 计算 `bytes` 的 64 位 FNV-1a 哈希值。
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |h, b| {
+        (h ^ *b as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+impl ValueTable {
+    /// 计算区域 `region` 原始字节的 64 位 FNV-1a 哈希值。
+    ///
+    /// 只有该区域的内容会参与计算，可用于判断单个区域自上次检查后是否发生变化。
+    pub fn region_hash(&self, region: Region) -> u64 {
+        fnv1a(self.region_bytes(region))
+    }
+
+    /// 计算整个存储表内容的 64 位哈希值，跳过全零的机器字。
+    ///
+    /// 仍需读取整个缓冲区，但只有非零的字（连同其序号）参与混合，对稀疏的存储表比逐字节的
+    /// [`Hash`] 实现快得多。结果只取决于缓冲区内容，内容相同的存储表哈希值相同；不同构建
+    /// 目标之间的结果不保证一致。
+    pub fn fast_hash(&self) -> u64 {
+        const W: usize = core::mem::size_of::<usize>();
+        let words = self.as_bytes().chunks_exact(W);
+        words.enumerate().fold(FNV_OFFSET_BASIS, |h, (i, w)| {
+            let w = usize::from_ne_bytes(w.try_into().unwrap());
+            if w == 0 {
+                return h;
+            }
+            let h = (h ^ i as u64).wrapping_mul(FNV_PRIME);
+            (h ^ w as u64).wrapping_mul(FNV_PRIME)
+        })
+    }
+}
+
+/// 将整个缓冲区的原始字节交给哈希器，与 [`PartialEq`] 的比较方式一致。
+///
+/// 耗时与缓冲区大小成正比（完整布局为 2 MiB），与写入了多少值无关；对稀疏的存储表可以
+/// 考虑 [`ValueTable::fast_hash`]。
+impl Hash for ValueTable {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write(self.as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a_vectors() {
+        assert_eq!(fnv1a(b""), 0xCBF2_9CE4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xAF63_DC4C_8601_EC8C);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_F739_67E8);
+    }
+
+    #[test]
+    fn region_scoped() {
+        let vt = ValueTable::new();
+        let h8 = vt.region_hash(Region::Bits8);
+        let h16 = vt.region_hash(Region::Bits16);
+        vt.set_u16(0x1234, 0x5678);
+        assert_ne!(vt.region_hash(Region::Bits16), h16);
+        assert_eq!(vt.region_hash(Region::Bits8), h8);
+        vt.set_u16(0x1234, 0);
+        assert_eq!(vt.region_hash(Region::Bits16), h16);
+    }
+
+    #[cfg(feature = "std")]
+    fn std_hash(vt: &ValueTable) -> u64 {
+        let mut h = std::collections::hash_map::DefaultHasher::new();
+        vt.hash(&mut h);
+        h.finish()
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn table_hash() {
+        let a = ValueTable::new();
+        let b = ValueTable::new();
+        for vt in [&a, &b] {
+            vt.set_bit(0x0010);
+            vt.set_u32(0x0100, 0xDEAD_BEEF);
+        }
+        assert_eq!(std_hash(&a), std_hash(&b));
+        assert_eq!(a.fast_hash(), b.fast_hash());
+
+        b.set_u64(0xFFFF, 1);
+        assert_ne!(std_hash(&a), std_hash(&b));
+        assert_ne!(a.fast_hash(), b.fast_hash());
+
+        let c = ValueTable::new();
+        c.set_u32(0x0101, 0xDEAD_BEEF);
+        c.set_bit(0x0010);
+        assert_ne!(a.fast_hash(), c.fast_hash());
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{Backing, RegionLayout, ValueTable};
+use core::ptr::NonNull;
+
+/// 大页的大小。
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+impl ValueTable {
+    /// 尝试以匿名大页映射布局 `layout` 的缓冲区，不足一个大页的布局或映射失败时返回 `None`。
+    ///
+    /// 映射长度向上取整到大页大小，匿名映射的内容已为零。
+    pub(crate) fn try_with_huge_pages(layout: RegionLayout) -> Option<Self> {
+        if layout.size() < HUGE_PAGE_SIZE {
+            return None;
+        }
+        let len = layout.size().div_ceil(HUGE_PAGE_SIZE) * HUGE_PAGE_SIZE;
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_HUGETLB,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return None;
+        }
+        Some(Self {
+            mem: NonNull::new(ptr.cast())?,
+            layout,
+            observers: Default::default(),
+            dirty: None,
+            presence: None,
+            version: Default::default(),
+            generations: Default::default(),
+            zeroize: false,
+            backing: Backing::HugePages { len },
+        })
+    }
+
+    /// 缓冲区是否分配在大页上。
+    ///
+    /// 系统未预留大页（`/proc/sys/vm/nr_hugepages` 为 `0`）时，构建存储表会退回普通分配。
+    pub fn uses_huge_pages(&self) -> bool {
+        matches!(self.backing, Backing::HugePages { .. })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Region, RegionConfig};
+
+    #[test]
+    fn alloc_or_fall_back() {
+        let vt = ValueTable::new();
+        vt.set_bit(0x0001);
+        vt.set_u16(0x0010, 0xBEEF);
+        vt.set_u128(0xFFFF, u128::MAX);
+        assert!(vt.get_bit(0x0001));
+        assert_eq!(vt.get_u16(0x0010), 0xBEEF);
+        assert_eq!(vt.get_u128(0xFFFF), u128::MAX);
+        assert_eq!(
+            vt.region_bytes(Region::Bits8)
+                .iter()
+                .filter(|b| **b != 0)
+                .count(),
+            0
+        );
+
+        let copy = vt.clone();
+        assert_eq!(copy.uses_huge_pages(), vt.uses_huge_pages());
+        assert_eq!(copy, vt);
+        drop(vt);
+        assert_eq!(copy.get_u16(0x0010), 0xBEEF);
+
+        let small = ValueTable::with_regions(RegionConfig {
+            bits16: 4,
+            ..RegionConfig::empty()
+        });
+        assert!(!small.uses_huge_pages());
+    }
+}
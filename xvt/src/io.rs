@@ -0,0 +1,66 @@
+use crate::ValueTable;
+use std::io;
+
+/// 流式写出时每次写入的字节数。
+const CHUNK_LEN: usize = 64 * 1024;
+
+impl ValueTable {
+    /// 将整个存储表的原始字节分块写入 `w`，内容与 [`ValueTable::snapshot`] 相同但不分配中间缓冲区。
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        for chunk in self.as_bytes().chunks(CHUNK_LEN) {
+            w.write_all(chunk)?;
+        }
+        Ok(())
+    }
+
+    /// 从 `r` 分块读取恰好与存储表大小相同的字节并覆盖整个存储表。
+    ///
+    /// 数据不足时返回 [`io::ErrorKind::UnexpectedEof`] 错误，此时存储表可能已被部分覆盖。
+    /// 与 [`ValueTable::restore`] 一样，读取不会触发回调或脏标记。
+    pub fn read_from<R: io::Read>(&self, r: &mut R) -> io::Result<()> {
+        let mut buf = [0u8; 4096];
+        let mut ofs = 0;
+        let size = self.layout.size();
+        while ofs < size {
+            let n = buf.len().min(size - ofs);
+            r.read_exact(&mut buf[..n])?;
+            unsafe {
+                core::ptr::copy_nonoverlapping(buf.as_ptr(), self.mem.as_ptr().add(ofs), n);
+            }
+            ofs += n;
+        }
+        self.generations.bump_all();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn round_trip() {
+        let vt = ValueTable::new();
+        vt.set_bit(0x0007);
+        vt.set_u16(0x1234, 0xBEEF);
+        vt.set_u128(0xFFFF, u128::MAX);
+
+        let mut cursor = Cursor::new(Vec::new());
+        vt.write_to(&mut cursor).unwrap();
+        assert_eq!(cursor.get_ref().len(), ValueTable::buffer_len());
+
+        cursor.set_position(0);
+        let copy = ValueTable::new();
+        copy.read_from(&mut cursor).unwrap();
+        assert!(copy.as_bytes() == vt.as_bytes());
+    }
+
+    #[test]
+    fn short_read() {
+        let vt = ValueTable::new();
+        let mut cursor = Cursor::new(vec![0u8; ValueTable::buffer_len() - 1]);
+        let err = vt.read_from(&mut cursor).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{Region, ValueTable};
+
+/// 按地址升序遍历单比特区域中已置位地址的迭代器。
+///
+/// 由 [`ValueTable::iter_set_bits`] 创建。
+pub struct SetBitsIter<'a> {
+    words: &'a [usize],
+    /// 当前字的序号。
+    index: usize,
+    /// 当前字中尚未遍历的位。
+    rest: usize,
+}
+
+impl Iterator for SetBitsIter<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        while self.rest == 0 {
+            self.index += 1;
+            self.rest = *self.words.get(self.index)?;
+        }
+        let bit = self.rest.trailing_zeros() as usize;
+        self.rest &= self.rest - 1;
+        Some((self.index * usize::BITS as usize + bit) as u16)
+    }
+}
+
+impl ValueTable {
+    /// 按地址升序遍历单比特区域中所有已置位的地址。
+    ///
+    /// 逐字扫描，跳过全为零的字，只产出置位的地址。
+    pub fn iter_set_bits(&self) -> SetBitsIter<'_> {
+        let words = self.bit_words();
+        SetBitsIter {
+            words,
+            index: 0,
+            rest: words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl ValueTable {
+    /// 单比特区域按机器字的视图。
+    fn bit_words(&self) -> &[usize] {
+        unsafe {
+            core::slice::from_raw_parts(
+                self.region_ptr(Region::Bit).cast::<usize>(),
+                self.region_len(Region::Bit) / core::mem::size_of::<usize>(),
+            )
+        }
+    }
+
+    /// 以闭区间 `[start, end]` 内的各个字及其有效位掩码依次调用 `f`，`f` 返回 `false` 时提前结束。
+    ///
+    /// 超出区域容量的部分不参与计算。
+    fn scan_bit_span(&self, start: u16, end: u16, mut f: impl FnMut(usize, usize) -> bool) {
+        let words = self.bit_words();
+        let cap = self.region_capacity(Region::Bit);
+        if start > end || start as usize >= cap {
+            return;
+        }
+        let end = (end as usize).min(cap - 1);
+        let n = usize::BITS as usize;
+        let (first, last) = (start as usize / n, end / n);
+        for (i, word) in words.iter().enumerate().take(last + 1).skip(first) {
+            let mut mask = usize::MAX;
+            if i == first {
+                mask &= usize::MAX << (start as usize % n);
+            }
+            if i == last {
+                mask &= usize::MAX >> (n - 1 - end % n);
+            }
+            if !f(*word, mask) {
+                return;
+            }
+        }
+    }
+
+    /// 统计单比特区域闭区间 `[start, end]` 内已置位的个数。
+    ///
+    /// `start` 大于 `end` 时区间为空，超出区域容量的地址视为未置位。
+    pub fn count_set_bits(&self, start: u16, end: u16) -> u32 {
+        let mut count = 0;
+        self.scan_bit_span(start, end, |word, mask| {
+            count += (word & mask).count_ones();
+            true
+        });
+        count
+    }
+
+    /// 单比特区域闭区间 `[start, end]` 内是否有任一位已置位。
+    pub fn any_set(&self, start: u16, end: u16) -> bool {
+        let mut any = false;
+        self.scan_bit_span(start, end, |word, mask| {
+            any = word & mask != 0;
+            !any
+        });
+        any
+    }
+
+    /// 单比特区域闭区间 `[start, end]` 内是否所有位都已置位。
+    ///
+    /// 空区间返回 `true`；区间超出区域容量时返回 `false`。
+    pub fn all_set(&self, start: u16, end: u16) -> bool {
+        if start <= end && end as usize >= self.region_capacity(Region::Bit) {
+            return false;
+        }
+        let mut all = true;
+        self.scan_bit_span(start, end, |word, mask| {
+            all = word & mask == mask;
+            all
+        });
+        all
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RegionConfig;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn iter_set_bits() {
+        let vt = ValueTable::new();
+        assert_eq!(vt.iter_set_bits().next(), None);
+        let addrs = [0, 63, 64, 1000, 0x8000, 65535];
+        for addr in addrs.iter().rev() {
+            vt.set_bit(*addr);
+        }
+        assert_eq!(vt.iter_set_bits().collect::<Vec<_>>(), addrs);
+        vt.clear_bit(63);
+        assert_eq!(vt.iter_set_bits().count(), addrs.len() - 1);
+    }
+
+    #[test]
+    fn bit_spans() {
+        let vt = ValueTable::new();
+        for addr in 60..=130 {
+            vt.set_bit(addr);
+        }
+        vt.set_bit(0xFFFF);
+        assert_eq!(vt.count_set_bits(0, 0xFFFF), 72);
+        assert_eq!(vt.count_set_bits(61, 129), 69);
+        assert_eq!(vt.count_set_bits(0, 59), 0);
+        assert_eq!(vt.count_set_bits(100, 100), 1);
+        assert_eq!(vt.count_set_bits(131, 131), 0);
+        assert_eq!(vt.count_set_bits(130, 60), 0);
+
+        assert!(vt.any_set(0, 60));
+        assert!(vt.any_set(0xFFFF, 0xFFFF));
+        assert!(!vt.any_set(131, 0xFFFE));
+        assert!(!vt.any_set(0, 59));
+
+        assert!(vt.all_set(60, 130));
+        assert!(vt.all_set(63, 64));
+        assert!(!vt.all_set(59, 130));
+        assert!(!vt.all_set(60, 131));
+        assert!(!vt.all_set(0, 0xFFFF));
+        assert!(vt.all_set(0xFFFF, 0xFFFF));
+
+        for addr in 0..=0xFFFF {
+            vt.set_bit(addr);
+        }
+        assert!(vt.all_set(0, 0xFFFF));
+        assert_eq!(vt.count_set_bits(0, 0xFFFF), 65536);
+    }
+
+    #[test]
+    fn bit_spans_capacity() {
+        let vt = ValueTable::with_regions(RegionConfig {
+            bits: 64,
+            ..RegionConfig::empty()
+        });
+        for addr in 0..64 {
+            vt.set_bit(addr);
+        }
+        assert_eq!(vt.count_set_bits(0, 0xFFFF), 64);
+        assert!(vt.all_set(0, 63));
+        assert!(!vt.all_set(0, 64));
+        assert!(!vt.any_set(64, 0xFFFF));
+    }
+}
@@ -1,118 +1,429 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(dead_code)]
 
+extern crate alloc;
+
+use alloc::alloc::{alloc_zeroed, dealloc};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ptr::NonNull;
+use core::sync::atomic::AtomicU64;
+use dirty::DirtyMap;
+use generation::Generations;
+use observe::Observers;
 use paste::paste;
-use std::alloc::{alloc_zeroed, dealloc, Layout};
-use std::ptr::{self, NonNull};
+use region::RegionLayout;
+
+mod addr;
+mod alarm;
+mod atomic;
+#[cfg(feature = "std")]
+mod audit;
+mod builder;
+mod bulk;
+mod canary;
+mod const_table;
+#[cfg(feature = "std")]
+mod csv;
+mod dirty;
+mod dump;
+mod error;
+mod field;
+mod generation;
+mod hash;
+#[cfg(all(feature = "hugepages", target_os = "linux"))]
+mod hugepages;
+#[cfg(feature = "std")]
+mod io;
+mod iter;
+mod merge;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod observe;
+mod order;
+mod patch;
+#[cfg(feature = "std")]
+mod pool;
+mod presence;
+mod region;
+mod registers;
+mod scaled;
+mod seqlock;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod snapshot;
+mod stats;
+mod swap;
+#[cfg(feature = "std")]
+mod sync;
+mod view;
+
+pub use addr::{
+    Address, BitAddr, F32Addr, F64Addr, I128Addr, I16Addr, I32Addr, I64Addr, I8Addr, U128Addr,
+    U16Addr, U32Addr, U64Addr, U8Addr,
+};
+pub use alarm::CrossDirection;
+#[cfg(feature = "std")]
+pub use audit::{AuditEntry, AuditKind, AuditedValueTable};
+pub use builder::ValueTableBuilder;
+pub use const_table::{ConstValueTable, ConstValueTableRef};
+#[cfg(feature = "std")]
+pub use csv::CsvOptions;
+#[cfg(feature = "std")]
+pub use error::LoadError;
+pub use error::{AllocError, CanaryError, LayoutError, XvtError};
+pub use iter::SetBitsIter;
+pub use merge::MergePolicy;
+#[cfg(feature = "std")]
+pub use observe::ChangeCallback;
+pub use order::ByteOrder;
+#[cfg(feature = "std")]
+pub use pool::{PooledValueTable, ValueTablePool};
+pub use region::{Region, RegionConfig};
+pub use registers::modbus::{
+    modbus_crc16, modbus_exception, plan_reads, ExceptionCode, ReadRequest,
+};
+pub use registers::{RegError, Registers};
+pub use stats::{NumericStats, RegionStats};
+pub use swap::{swap_bytes_u16, swap_bytes_u16_scalar};
+#[cfg(feature = "std")]
+pub use sync::SyncValueTable;
+pub use view::U16View;
 
 /// 一个代表多种值存储表的类型。
 ///
+/// # 存储别名
+///
+/// 同一宽度的有符号、无符号整数及浮点值共用同一个区域：`set_u16(addr, ..)` 与 `set_i16(addr, ..)`
+/// 写入的是同一位置，`set_u32`、`set_i32` 与 `set_f32` 同理。读取时按所用的类型解释存储的位。
+///
+/// ```
+/// use xvt::ValueTable;
+///
+/// let vt = ValueTable::new();
+/// vt.set_u16(0x0001, 0xFFFF);
+/// assert_eq!(vt.get_i16(0x0001), -1);
+/// ```
+///
 /// # Examples
 ///
 /// ```
 /// use xvt::ValueTable;
-/// 
+///
 /// let vt = ValueTable::new();
 /// vt.set_bit(0x1000);
 /// assert_eq!(vt.get_bit(0x1000), true);
 /// ```
 pub struct ValueTable {
     mem: NonNull<u8>,
+    layout: RegionLayout,
+    observers: Observers,
+    dirty: Option<DirtyMap>,
+    /// 供 [`ValueTable::new_presence_tracked`] 使用的已写入地址位图。
+    presence: Option<DirtyMap>,
+    /// 供 [`ValueTable::write_consistent`] 使用的版本号。
+    version: AtomicU64,
+    /// 供 [`ValueTable::region_generation`] 使用的各区域变化代数。
+    generations: Generations,
+    backing: Backing,
+    /// 释放前是否以零覆盖整个缓冲区。
+    zeroize: bool,
+}
+
+/// 存储表内存的来源。
+enum Backing {
+    /// 由全局分配器分配的内存。
+    Heap,
+    /// 借用自 [`ConstValueTable`] 的内存，由其所有者负责释放。
+    Borrowed,
+    /// 映射到文件的内存。
+    #[cfg(feature = "mmap")]
+    Mmap(memmap2::MmapMut),
+    /// 以 `MAP_HUGETLB` 映射的匿名大页内存，`len` 为映射长度。
+    #[cfg(all(feature = "hugepages", target_os = "linux"))]
+    HugePages { len: usize },
+}
+
+impl Backing {
+    /// 是否为存储表自行分配、可由对象池复用的内存。
+    fn is_owned(&self) -> bool {
+        match self {
+            Backing::Heap => true,
+            #[cfg(all(feature = "hugepages", target_os = "linux"))]
+            Backing::HugePages { .. } => true,
+            _ => false,
+        }
+    }
 }
 
 macro_rules! impl_bits {
     ($t:ty, $n:expr) => {
         paste! {
+            #[doc = "获取指定地址 `addr` 类型为 `" $t "` 的值的指针，超出区域容量时返回 `None`。"]
+            fn [<$t _ptr>](&self, addr: u16) -> Option<*mut $t> {
+                let region = Region::[<Bits $n>];
+                if addr as usize >= self.layout.capacity(region) {
+                    return None;
+                }
+                unsafe { Some(self.region_ptr(region).cast::<$t>().add(addr as usize)) }
+            }
+
             #[doc = "获取指定地址 `addr` 类型为 `" $t "` 的值。"]
             pub fn [<get_ $t>](&self, addr: u16) -> $t {
-                unsafe {
-                    let ofs = Self::[<BITS_ $n _REG_OFS>] as isize;
-                    let val_ptr = self.mem.as_ptr().offset(ofs).cast::<$t>().offset(addr as isize);
-                    *val_ptr
+                match self.[<$t _ptr>](addr) {
+                    Some(val_ptr) => unsafe { *val_ptr },
+                    None => 0 as $t,
                 }
             }
 
+            #[doc = "获取指定地址 `addr` 类型为 `" $t "` 的值，超出区域容量时返回 `None`。"]
+            ///
+            /// 与返回 `0` 的普通访问器不同，可以区分未分配的地址与值为 `0` 的地址。
+            pub fn [<get_ $t _checked>](&self, addr: u16) -> Option<$t> {
+                self.[<$t _ptr>](addr).map(|val_ptr| unsafe { *val_ptr })
+            }
+
             #[doc = "获取指定地址 `addr` 类型为 `" $t "` 的 `num` 个值。"]
             pub fn [<get_ $t s>](&self, addr: u16, num: u16) -> &[$t] {
-                unsafe {
-                    let ofs = Self::[<BITS_ $n _REG_OFS>] as isize;
-                    let val_ptr = self.mem.as_ptr().offset(ofs).cast::<$t>().offset(addr as isize);
-                    let m = 65536 - addr as usize;
-                    std::slice::from_raw_parts(val_ptr, m.min(num as usize))
+                match self.[<$t _ptr>](addr) {
+                    Some(val_ptr) => unsafe {
+                        let m = self.layout.capacity(Region::[<Bits $n>]) - addr as usize;
+                        core::slice::from_raw_parts(val_ptr, m.min(num as usize))
+                    },
+                    None => &[],
                 }
             }
 
+            #[doc = "将从指定地址 `addr` 开始类型为 `" $t "` 的值复制到 `out`，返回实际复制的值个数。"]
+            ///
+            /// 最多复制 `out.len()` 个值，超出区域容量的部分将被截去，`out` 中其余的元素保持不变。
+            pub fn [<get_ $t s_into>](&self, addr: u16, out: &mut [$t]) -> usize {
+                let cap = self.layout.capacity(Region::[<Bits $n>]);
+                let n = cap.saturating_sub(addr as usize).min(out.len());
+                if let Some(val_ptr) = self.[<$t _ptr>](addr) {
+                    unsafe { core::ptr::copy_nonoverlapping(val_ptr, out.as_mut_ptr(), n) };
+                }
+                n
+            }
+
+            #[doc = "复制指定地址 `addr` 类型为 `" $t "` 的 `num` 个值，超出区域容量的部分将被截去。"]
+            ///
+            /// 返回的数据不借用存储表，可在释放存储表（或其锁）后继续使用。
+            pub fn [<copy_ $t s>](&self, addr: u16, num: u16) -> Vec<$t> {
+                self.[<get_ $t s>](addr, num).to_vec()
+            }
+
             #[doc = "设置指定地址 `addr` 类型为 `" $t "` 的值。"]
+            ///
+            /// 与同一宽度的其他类型共用存储，参见 [`ValueTable`] 的存储别名说明。
             pub fn [<set_ $t>](&self, addr: u16, val: $t) {
-                unsafe {
-                    let ofs = Self::[<BITS_ $n _REG_OFS>] as isize;
-                    let val_ptr = self.mem.as_ptr().offset(ofs).cast::<$t>().offset(addr as isize);
-                    *val_ptr = val;
+                if let Some(val_ptr) = self.[<$t _ptr>](addr) {
+                    let old = unsafe { val_ptr.replace(val) };
+                    self.on_written(Region::[<Bits $n>], addr, old.to_ne_bytes() != val.to_ne_bytes());
                 }
             }
 
-            #[doc = "设置指定地址 `addr` 类型为 `" $t "` 的多个值。"]
-            pub fn [<set_ $t s>](&self, addr: u16, vals: &[$t]) {
+            #[doc = "设置指定地址 `addr` 类型为 `" $t "` 的多个值，返回实际写入的值个数。"]
+            ///
+            /// 超出区域容量的部分将被忽略。
+            pub fn [<set_ $t s>](&self, addr: u16, vals: &[$t]) -> usize {
+                match self.[<$t _ptr>](addr) {
+                    Some(val_ptr) => unsafe {
+                        let m = self.layout.capacity(Region::[<Bits $n>]) - addr as usize;
+                        let n = m.min(vals.len());
+                        if self.hooked() {
+                            // `vals` 可能与目标重叠，目标与其重叠且在后时从尾部开始逐个写入，保证每个值在被覆盖前已读出。
+                            let src = vals.as_ptr();
+                            let write = |i: usize| {
+                                let val = src.add(i).read();
+                                let old = val_ptr.add(i).replace(val);
+                                self.on_written(Region::[<Bits $n>], addr + i as u16, old.to_ne_bytes() != val.to_ne_bytes());
+                            };
+                            let dst = val_ptr as *const $t;
+                            if src < dst && dst < src.add(n) {
+                                (0..n).rev().for_each(write);
+                            } else {
+                                (0..n).for_each(write);
+                            }
+                        } else {
+                            core::ptr::copy(vals.as_ptr(), val_ptr, n);
+                        }
+                        n
+                    },
+                    None => 0,
+                }
+            }
+
+            #[doc = "以类型为 `" $t "` 的可变切片形式获取整个 " $n " 位区域，长度为该区域的容量。"]
+            ///
+            /// `&mut self` 保证了访问的独占性，经切片的修改不会触发回调或脏标记。
+            pub fn [<region_mut_ $t>](&mut self) -> &mut [$t] {
+                let region = Region::[<Bits $n>];
+                self.generations.bump(region);
                 unsafe {
-                    let ofs = Self::[<BITS_ $n _REG_OFS>] as isize;
-                    let val_ptr = self.mem.as_ptr().offset(ofs).cast::<$t>().offset(addr as isize);
-                    let m = 65536 - addr as usize;
-                    let n = vals.len();
-                    std::ptr::copy(vals.as_ptr(), val_ptr, m.min(n));
+                    core::slice::from_raw_parts_mut(
+                        self.region_ptr(region).cast::<$t>(),
+                        self.layout.capacity(region),
+                    )
                 }
             }
         }
     };
 }
 
+macro_rules! impl_reinterpret {
+    ($u:ty, $i:ty) => {
+        paste! {
+            #[doc = "将指定地址 `addr` 存储的 `" $u "` 值按 `" $i "` 解释，与 [`ValueTable::get_" $i "`] 读取同一位置。"]
+            pub fn [<get_ $u _as_ $i>](&self, addr: u16) -> $i {
+                self.[<get_ $u>](addr) as $i
+            }
+
+            #[doc = "将指定地址 `addr` 存储的 `" $i "` 值按 `" $u "` 解释，与 [`ValueTable::get_" $u "`] 读取同一位置。"]
+            pub fn [<get_ $i _as_ $u>](&self, addr: u16) -> $u {
+                self.[<get_ $i>](addr) as $u
+            }
+        }
+    };
+}
+
 impl ValueTable {
-    const BITS_8_REG_OFS: usize = 8192;
-    const BITS_16_REG_OFS: usize = Self::BITS_8_REG_OFS + 65536;
-    const BITS_32_REG_OFS: usize = Self::BITS_16_REG_OFS + 131072;
-    const BITS_64_REG_OFS: usize = Self::BITS_32_REG_OFS + 262144;
+    /// 完整布局下各区域可容纳的值个数。
+    const BIT_REG_CAP: usize = 65536;
+    const BITS_8_REG_CAP: usize = 65536;
+    const BITS_16_REG_CAP: usize = 65536;
+    const BITS_32_REG_CAP: usize = 65536;
+    const BITS_64_REG_CAP: usize = 65536;
+    const BITS_128_REG_CAP: usize = 65536;
+
+    const BITS_8_REG_OFS: usize = Self::BIT_REG_CAP / 8;
+    const BITS_16_REG_OFS: usize = Self::BITS_8_REG_OFS + Self::BITS_8_REG_CAP;
+    const BITS_32_REG_OFS: usize = Self::BITS_16_REG_OFS + Self::BITS_16_REG_CAP * 2;
+    const BITS_64_REG_OFS: usize = Self::BITS_32_REG_OFS + Self::BITS_32_REG_CAP * 4;
+    const BITS_128_REG_OFS: usize = Self::BITS_64_REG_OFS + Self::BITS_64_REG_CAP * 8;
+    const BITS_128_REG_END: usize = Self::BITS_128_REG_OFS + Self::BITS_128_REG_CAP * 16;
 
-    /// 构建一个多种值存储表实例。
+    /// 完整布局下存储表占用的字节数。
+    const BUFFER_SIZE: usize = 2 * 1024 * 1024;
+
+    /// 构建一个多种值存储表实例，各区域按 [`RegionConfig::full`] 分配。
+    ///
+    /// # 中止
+    ///
+    /// 内存分配失败时调用 [`alloc::alloc::handle_alloc_error`] 中止进程，
+    /// 需要自行处理内存不足的场合请使用 [`ValueTable::try_new`]。
     pub fn new() -> Self {
-        unsafe {
-            let layout = Layout::from_size_align_unchecked(1024 * 1024, 4096);
-            let ptr = alloc_zeroed(layout);
-            Self {
-                mem: NonNull::new_unchecked(ptr),
-            }
+        Self::with_regions(RegionConfig::full())
+    }
+
+    /// 构建一个多种值存储表实例，内存分配失败时返回 [`AllocError`]。
+    pub fn try_new() -> Result<Self, AllocError> {
+        Self::try_with_regions(RegionConfig::full())
+    }
+
+    /// 构建一个在释放时以零覆盖整个缓冲区的存储表实例，适合存放密钥等敏感数据。
+    ///
+    /// 覆盖使用易失性写入，不会被编译器优化掉；由其复制出的存储表同样在释放时覆盖。
+    pub fn new_zeroizing() -> Self {
+        let mut vt = Self::new();
+        vt.zeroize = true;
+        vt
+    }
+
+    /// 构建一个每个字节都初始化为 `pattern` 的存储表实例，用于区分从未写入过的值。
+    ///
+    /// 各区域按其类型解释重复的字节：单比特值在 `pattern` 的对应位为 `1` 时读作置位
+    /// （`0xFF` 即全部置位），整数值为每个字节都是 `pattern` 的数，例如 `0xFF` 时 `u16` 读作
+    /// `0xFFFF`、`i16` 读作 `-1`，浮点值按相同的位模式解释（`0xFF` 时为 NaN）。超出区域容量的地址
+    /// 仍读作 `0`。
+    pub fn new_filled(pattern: u8) -> Self {
+        let vt = Self::new();
+        unsafe { vt.mem.as_ptr().write_bytes(pattern, vt.layout.size()) };
+        vt
+    }
+
+    /// 按区域配置 `config` 构建一个多种值存储表实例。
+    ///
+    /// 未分配或超出容量的地址读作 `0`，写入时被忽略。内存分配失败时中止进程，
+    /// 需要自行处理内存不足的场合请使用 [`ValueTable::try_with_regions`]。
+    pub fn with_regions(config: RegionConfig) -> Self {
+        let layout = RegionLayout::new(config);
+        match Self::try_with_layout(layout) {
+            Ok(vt) => vt,
+            Err(_) => alloc::alloc::handle_alloc_error(layout.alloc_layout()),
+        }
+    }
+
+    /// 按区域配置 `config` 构建一个多种值存储表实例，内存分配失败时返回 [`AllocError`]。
+    pub fn try_with_regions(config: RegionConfig) -> Result<Self, AllocError> {
+        Self::try_with_layout(RegionLayout::new(config))
+    }
+
+    fn try_with_layout(layout: RegionLayout) -> Result<Self, AllocError> {
+        #[cfg(all(feature = "hugepages", target_os = "linux"))]
+        if let Some(vt) = Self::try_with_huge_pages(layout) {
+            return Ok(vt);
+        }
+        let ptr = unsafe { alloc_zeroed(layout.alloc_layout()) };
+        let mem = NonNull::new(ptr).ok_or(AllocError)?;
+        Ok(Self {
+            mem,
+            layout,
+            observers: Observers::default(),
+            dirty: None,
+            presence: None,
+            version: AtomicU64::new(0),
+            generations: Generations::default(),
+            backing: Backing::Heap,
+            zeroize: false,
+        })
+    }
+
+    /// 以易失性写入将整个缓冲区清零。
+    fn wipe(&self) {
+        let words = self.mem.as_ptr().cast::<usize>();
+        for i in 0..self.layout.size() / core::mem::size_of::<usize>() {
+            unsafe { words.add(i).write_volatile(0) };
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// 计算单比特地址 `addr` 所在字的序号及其位掩码。
+    pub(crate) fn bit_location(addr: u16) -> (usize, usize) {
+        const N: u16 = core::mem::size_of::<usize>() as u16 * 8;
+        ((addr / N) as usize, 1 << (addr % N))
+    }
+
+    /// 计算单比特地址 `addr` 所在字的指针及其位掩码，超出区域容量时返回 `None`。
+    fn bit_ptr(&self, addr: u16) -> Option<(*mut usize, usize)> {
+        if addr as usize >= self.layout.capacity(Region::Bit) {
+            return None;
         }
+        let (offset, mask) = Self::bit_location(addr);
+        unsafe { Some((self.mem.cast::<usize>().as_ptr().add(offset), mask)) }
     }
 
     /// 获取指定地址 `addr` 的单比特值。
     pub fn get_bit(&self, addr: u16) -> bool {
-        unsafe {
-            const N: u16 = std::mem::size_of::<usize>() as u16;
-            let offset = addr / N;
-            let val: usize = self
-                .mem
-                .cast::<usize>()
-                .as_ptr()
-                .offset(offset as isize)
-                .read();
-            val & (1 << (addr % N)) != 0
+        match self.bit_ptr(addr) {
+            Some((val_ptr, mask)) => unsafe { val_ptr.read() & mask != 0 },
+            None => false,
         }
     }
 
     /// 清除指定地址 `addr` 的单比特值。
     pub fn clear_bit(&self, addr: u16) {
-        unsafe {
-            const N: u16 = std::mem::size_of::<usize>() as u16;
-            let offset = addr / N;
-            let val_ptr = self.mem.cast::<usize>().as_ptr().offset(offset as isize);
-            *val_ptr &= !(1 << (addr % N));
+        if let Some((val_ptr, mask)) = self.bit_ptr(addr) {
+            let old = unsafe { val_ptr.replace(val_ptr.read() & !mask) };
+            self.on_written(Region::Bit, addr, old & mask != 0);
         }
     }
 
     /// 设置指定地址 `addr` 的单比特值。
     pub fn set_bit(&self, addr: u16) {
-        unsafe {
-            const N: u16 = std::mem::size_of::<usize>() as u16;
-            let offset = addr / N;
-            let val_ptr = self.mem.cast::<usize>().as_ptr().offset(offset as isize);
-            *val_ptr |= 1 << (addr % N);
+        if let Some((val_ptr, mask)) = self.bit_ptr(addr) {
+            let old = unsafe { val_ptr.replace(val_ptr.read() | mask) };
+            self.on_written(Region::Bit, addr, old & mask == 0);
         }
     }
 
@@ -124,13 +435,201 @@ impl ValueTable {
     impl_bits!(u16, 16);
     impl_bits!(u32, 32);
     impl_bits!(u64, 64);
+    impl_bits!(f32, 32);
+    impl_bits!(f64, 64);
+    impl_bits!(i128, 128);
+    impl_bits!(u128, 128);
+
+    impl_reinterpret!(u8, i8);
+    impl_reinterpret!(u16, i16);
+    impl_reinterpret!(u32, i32);
+    impl_reinterpret!(u64, i64);
+
+    /// 获取整个存储表的原始字节。
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.mem.as_ptr(), self.layout.size()) }
+    }
+
+    /// 获取整个存储表缓冲区的指针及字节数，供 FFI 或 DMA 等场合直接访问。
+    ///
+    /// 各区域在缓冲区中的位置由 [`ValueTable::region_offset`] 和 [`ValueTable::region_len`] 给出。
+    ///
+    /// # Safety
+    ///
+    /// 通过该指针访问期间，调用者必须保证没有其他代码经由存储表的 API 并发访问同一内存，
+    /// 写入时须遵守区域布局及各类型的对齐要求，且不得在存储表释放后继续使用该指针。
+    /// 经由该指针的写入不会触发变化回调或脏标记。
+    pub unsafe fn as_raw_parts(&self) -> (*mut u8, usize) {
+        (self.mem.as_ptr(), self.layout.size())
+    }
+
+    /// 完整布局（[`RegionConfig::full`]）下存储表缓冲区的字节数。
+    pub const fn buffer_len() -> usize {
+        Self::BUFFER_SIZE
+    }
+
+    /// 区域 `region` 在本存储表中的字节偏移。
+    pub fn region_offset(&self, region: Region) -> usize {
+        self.layout.offset(region)
+    }
+
+    /// 区域 `region` 在本存储表中占用的字节数。
+    pub fn region_len(&self, region: Region) -> usize {
+        self.layout.byte_len(region)
+    }
+
+    /// 区域 `region` 在本存储表中可容纳的值个数，未分配的区域为 `0`。
+    pub fn region_capacity(&self, region: Region) -> usize {
+        self.layout.capacity(region)
+    }
+
+    /// 以切片形式获取整个 16 位区域，长度为该区域的容量。
+    pub fn as_u16_slice(&self) -> &[u16] {
+        self.region_u16s(Region::Bits16)
+    }
+
+    /// 以可变切片形式获取整个 16 位区域，长度为该区域的容量。
+    ///
+    /// 与以 `&self` 写入的 `set_*` 系列不同，`&mut self` 保证了访问的独占性，因此该切片是安全的。
+    pub fn as_u16_slice_mut(&mut self) -> &mut [u16] {
+        self.region_mut_u16()
+    }
+
+    /// 获取指定区域 `region` 的起始指针。
+    pub(crate) fn region_ptr(&self, region: Region) -> *mut u8 {
+        unsafe { self.mem.as_ptr().add(self.layout.offset(region)) }
+    }
+
+    /// 以类型为 `u16` 的切片形式获取指定区域 `region`。
+    pub(crate) fn region_u16s(&self, region: Region) -> &[u16] {
+        unsafe {
+            core::slice::from_raw_parts(
+                self.region_ptr(region).cast::<u16>(),
+                self.layout.byte_len(region) / 2,
+            )
+        }
+    }
+
+    /// 以类型为 `u16` 的可变切片形式获取指定区域 `region`。
+    pub(crate) fn region_u16s_mut(&mut self, region: Region) -> &mut [u16] {
+        unsafe {
+            core::slice::from_raw_parts_mut(
+                self.region_ptr(region).cast::<u16>(),
+                self.layout.byte_len(region) / 2,
+            )
+        }
+    }
+
+    /// 获取指定区域 `region` 的原始字节。
+    pub(crate) fn region_bytes(&self, region: Region) -> &[u8] {
+        unsafe {
+            core::slice::from_raw_parts(self.region_ptr(region), self.layout.byte_len(region))
+        }
+    }
+}
+
+const _: () = assert!(ValueTable::BITS_128_REG_END <= ValueTable::BUFFER_SIZE);
+
+// 带类型的访问器把区域基址加字节偏移直接转换为 `*mut T`，
+// 因此完整布局中每个区域的偏移都必须是对应类型对齐值的整数倍。
+const _: () = {
+    use core::mem::align_of;
+    assert!(ValueTable::BITS_16_REG_OFS.is_multiple_of(align_of::<u16>()));
+    assert!(ValueTable::BITS_32_REG_OFS.is_multiple_of(align_of::<u32>()));
+    assert!(ValueTable::BITS_32_REG_OFS.is_multiple_of(align_of::<f32>()));
+    assert!(ValueTable::BITS_64_REG_OFS.is_multiple_of(align_of::<u64>()));
+    assert!(ValueTable::BITS_64_REG_OFS.is_multiple_of(align_of::<f64>()));
+    assert!(ValueTable::BITS_128_REG_OFS.is_multiple_of(align_of::<u128>()));
+    assert!(RegionLayout::ALIGN.is_multiple_of(align_of::<u128>()));
+    assert!(RegionLayout::ALIGN.is_multiple_of(align_of::<usize>()));
+};
+
+impl Default for ValueTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 两个存储表的布局相同且缓冲区逐字节相同时相等。
+///
+/// 比较的是存储的位而不是值：同一位置以 `set_u16` 和 `set_i16` 写入的相同位模式相等，
+/// 位模式相同的 NaN 也相等。回调、脏标记等状态不参与比较。
+impl PartialEq for ValueTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.layout == other.layout && self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for ValueTable {}
+
+impl Clone for ValueTable {
+    /// 复制出一个内容相同的独立存储表。
+    ///
+    /// 副本总是分配在堆上，即使源存储表映射自文件；源存储表开启了脏标记跟踪时，
+    /// 副本同样开启跟踪，但脏标记初始为空。源存储表在释放时清零缓冲区时，副本同样如此。
+    fn clone(&self) -> Self {
+        let mut vt = match Self::try_with_layout(self.layout) {
+            Ok(vt) => vt,
+            Err(_) => alloc::alloc::handle_alloc_error(self.layout.alloc_layout()),
+        };
+        unsafe {
+            core::ptr::copy_nonoverlapping(self.mem.as_ptr(), vt.mem.as_ptr(), self.layout.size());
+        }
+        vt.dirty = self.dirty.as_ref().map(|_| DirtyMap::new());
+        vt.presence = self.presence.as_ref().map(DirtyMap::copy);
+        vt.zeroize = self.zeroize;
+        vt
+    }
+}
+
+/// 只输出存储表的概要：缓冲区大小、已置位的单比特值个数以及各数值区域中非零值的个数。
+impl fmt::Debug for ValueTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const W: usize = core::mem::size_of::<usize>();
+        let set_bits: u32 = self
+            .region_bytes(Region::Bit)
+            .chunks_exact(W)
+            .map(|w| usize::from_ne_bytes(w.try_into().unwrap()).count_ones())
+            .sum();
+        let nonzero = |region: Region| {
+            self.region_bytes(region)
+                .chunks_exact(region.element_size())
+                .filter(|v| v.iter().any(|b| *b != 0))
+                .count()
+        };
+        f.debug_struct("ValueTable")
+            .field("size", &self.layout.size())
+            .field("set_bits", &set_bits)
+            .field("nonzero_8", &nonzero(Region::Bits8))
+            .field("nonzero_16", &nonzero(Region::Bits16))
+            .field("nonzero_32", &nonzero(Region::Bits32))
+            .field("nonzero_64", &nonzero(Region::Bits64))
+            .field("nonzero_128", &nonzero(Region::Bits128))
+            .finish()
+    }
 }
 
 impl Drop for ValueTable {
     fn drop(&mut self) {
-        unsafe {
-            let layout = Layout::from_size_align_unchecked(1024 * 1024, 4096);
-            dealloc(self.mem.as_ptr(), layout);
+        match &self.backing {
+            Backing::Heap => unsafe {
+                if self.zeroize {
+                    self.wipe();
+                }
+                dealloc(self.mem.as_ptr(), self.layout.alloc_layout());
+            },
+            Backing::Borrowed => {}
+            #[cfg(all(feature = "hugepages", target_os = "linux"))]
+            Backing::HugePages { len } => unsafe {
+                if self.zeroize {
+                    self.wipe();
+                }
+                libc::munmap(self.mem.as_ptr().cast(), *len);
+            },
+            #[cfg(feature = "mmap")]
+            Backing::Mmap(mmap) => {
+                let _ = mmap.flush();
+            }
         }
     }
 }
@@ -141,16 +640,244 @@ unsafe impl Sync for ValueTable {}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::format;
+
+    #[test]
+    fn try_new() {
+        let vt = ValueTable::try_new().unwrap();
+        assert!(vt.as_bytes().iter().all(|b| *b == 0));
+        let vt = ValueTable::default();
+        assert!(!vt.get_bit(0xFFFF));
+    }
+
+    #[test]
+    fn clone() {
+        let vt = ValueTable::new();
+        vt.set_bit(0x0007);
+        vt.set_u16(0x0100, 0x1234);
+        vt.set_f64(0xFFFF, 1.5);
+        let copy = vt.clone();
+        assert!(copy.as_bytes() == vt.as_bytes());
+        copy.clear_bit(0x0007);
+        copy.set_u16(0x0100, 0x4321);
+        assert!(vt.get_bit(0x0007));
+        assert_eq!(vt.get_u16(0x0100), 0x1234);
+        assert_eq!(copy.get_f64(0xFFFF), 1.5);
+        drop(copy);
+        assert_eq!(vt.get_f64(0xFFFF), 1.5);
+    }
+
+    #[test]
+    fn u16_slice() {
+        let mut vt = ValueTable::new();
+        assert_eq!(vt.as_u16_slice().len(), 65536);
+        let vals = vt.as_u16_slice_mut();
+        vals[0x0000] = 1;
+        vals[0xFFFF] = 0xBEEF;
+        assert_eq!(vt.get_u16(0x0000), 1);
+        assert_eq!(vt.get_u16(0xFFFF), 0xBEEF);
+        assert_eq!(vt.get_u32(0x0000), 0);
+        vt.set_u16(0x1234, 7);
+        assert_eq!(vt.as_u16_slice()[0x1234], 7);
+    }
+
+    #[test]
+    fn region_mut() {
+        let mut vt = ValueTable::with_regions(RegionConfig {
+            bits16: 4,
+            bits64: 2,
+            ..RegionConfig::empty()
+        });
+        let vals = vt.region_mut_u16();
+        assert_eq!(vals.len(), 4);
+        vals.copy_from_slice(&[4, 3, 2, 1]);
+        vals.sort();
+        assert_eq!(vt.get_u16s(0x0000, 4), &[1, 2, 3, 4]);
+        vt.region_mut_f64()[1] = 2.5;
+        assert_eq!(vt.get_f64(0x0001), 2.5);
+        assert!(vt.region_mut_u32().is_empty());
+    }
+
+    #[test]
+    fn debug() {
+        let vt = ValueTable::new();
+        for addr in [0x0000, 0x0040, 0xFFFF] {
+            vt.set_bit(addr);
+        }
+        vt.set_u16(0x0001, 1);
+        vt.set_f64(0x0002, -0.0);
+        assert_eq!(
+            format!("{:?}", vt),
+            "ValueTable { size: 2097152, set_bits: 3, nonzero_8: 0, nonzero_16: 1, \
+             nonzero_32: 0, nonzero_64: 1, nonzero_128: 0 }"
+        );
+    }
+
+    #[test]
+    fn u128_round_trip() {
+        let vt = ValueTable::new();
+        for addr in [0x0000, 0x0001, 0x8000, 0xFFFE, 0xFFFF] {
+            vt.set_u128(addr, u128::MAX - addr as u128);
+        }
+        for addr in [0x0000, 0x0001, 0x8000, 0xFFFE, 0xFFFF] {
+            assert_eq!(vt.get_u128(addr), u128::MAX - addr as u128);
+        }
+        vt.set_i128(0x0002, i128::MIN);
+        assert_eq!(vt.get_i128(0x0002), i128::MIN);
+        assert_eq!(vt.set_u128s(0xFFFE, &[1, 2, 3]), 2);
+        assert_eq!(vt.get_u128s(0xFFFE, 4), &[1, 2]);
+        assert_eq!(vt.get_u64(0xFFFF), 0);
+    }
+
+    #[test]
+    fn raw_parts() {
+        let vt = ValueTable::new();
+        vt.set_u16(0x0102, 0xBEEF);
+        let (ptr, len) = unsafe { vt.as_raw_parts() };
+        assert_eq!(len, ValueTable::buffer_len());
+        let ofs = vt.region_offset(Region::Bits16) + 0x0102 * 2;
+        assert_eq!(unsafe { ptr.add(ofs).cast::<u16>().read() }, 0xBEEF);
+        unsafe { ptr.add(vt.region_offset(Region::Bits8) + 7).write(0x5A) };
+        assert_eq!(vt.get_u8(0x0007), 0x5A);
+    }
+
+    #[test]
+    fn signed_alias() {
+        let vt = ValueTable::new();
+        vt.set_u16(0x0001, 0xFFFF);
+        assert_eq!(vt.get_u16_as_i16(0x0001), -1);
+        assert_eq!(vt.get_i16(0x0001), -1);
+        vt.set_i32(0xFFFF, -2);
+        assert_eq!(vt.get_i32_as_u32(0xFFFF), 0xFFFF_FFFE);
+        vt.set_u8(0x0000, 0x80);
+        assert_eq!(vt.get_u8_as_i8(0x0000), i8::MIN);
+        vt.set_i64(0x0002, i64::MIN);
+        assert_eq!(vt.get_i64_as_u64(0x0002), 1 << 63);
+    }
+
+    #[test]
+    fn zeroizing() {
+        let vt = ValueTable::new_zeroizing();
+        vt.set_bit(0xFFFF);
+        vt.set_u64(0x0001, u64::MAX);
+        vt.set_u128(0xFFFF, u128::MAX);
+        let copy = vt.clone();
+        assert!(copy.zeroize);
+        assert!(!ValueTable::new().zeroize);
+        vt.wipe();
+        assert!(vt.as_bytes().iter().all(|b| *b == 0));
+        assert_eq!(copy.get_u128(0xFFFF), u128::MAX);
+    }
+
+    #[test]
+    fn equality() {
+        let a = ValueTable::new();
+        let b = ValueTable::new_tracked();
+        for vt in [&a, &b] {
+            vt.set_bit(0x0100);
+            vt.set_f32(0x0001, f32::NAN);
+        }
+        a.set_u16(0x0002, 0xFFFF);
+        b.set_i16(0x0002, -1);
+        assert!(a == b);
+        b.set_u8(0xFFFF, 1);
+        assert!(a != b);
+        assert!(a.clone() == a);
+        assert!(ValueTable::with_regions(RegionConfig::empty()) != a);
+    }
+
+    #[test]
+    fn filled() {
+        let vt = ValueTable::new_filled(0xFF);
+        assert_eq!(vt.get_u16(0x0000), 0xFFFF);
+        assert_eq!(vt.get_i32(0xFFFF), -1);
+        assert!(vt.get_bit(0x0000) && vt.get_bit(0xFFFF));
+        assert!(vt.get_f64(0x0001).is_nan());
+
+        let vt = ValueTable::new_filled(0x5A);
+        assert_eq!(vt.get_u32(0x0010), 0x5A5A_5A5A);
+        assert!(vt.get_bit(1) && !vt.get_bit(0));
+    }
+
+    #[test]
+    fn checked() {
+        let vt = ValueTable::with_regions(RegionConfig {
+            bits16: 0x8000,
+            ..RegionConfig::empty()
+        });
+        vt.set_u16(0x7FFF, 5);
+        assert_eq!(vt.get_u16_checked(0x7FFF), Some(5));
+        assert_eq!(vt.get_u16_checked(0x0000), Some(0));
+        assert_eq!(vt.get_u16_checked(0x8000), None);
+        assert_eq!(vt.get_u32_checked(0x0000), None);
+        assert_eq!(ValueTable::new().get_f64_checked(0xFFFF), Some(0.0));
+    }
+
+    #[test]
+    fn values_into() {
+        let vt = ValueTable::new();
+        vt.set_u16s(0xFFFD, &[1, 2, 3]);
+        let mut out = [9u16; 8];
+        assert_eq!(vt.get_u16s_into(0xFFFD, &mut out), 3);
+        assert_eq!(out, [1, 2, 3, 9, 9, 9, 9, 9]);
+        assert_eq!(vt.get_u16s_into(0xFFFE, &mut out[..1]), 1);
+        assert_eq!(out[0], 2);
+        assert_eq!(vt.get_u16s_into(0x0000, &mut []), 0);
+
+        let small = ValueTable::with_regions(RegionConfig::empty());
+        assert_eq!(small.get_f64s_into(0x0000, &mut [0.0; 4]), 0);
+    }
+
+    #[test]
+    fn overlapping_values() {
+        for vt in [ValueTable::new(), ValueTable::new_tracked()] {
+            vt.set_u16s(0x0000, &[0x100, 0x101, 0x102, 0x103]);
+            vt.set_u16s(0x0001, vt.get_u16s(0x0000, 4));
+            assert_eq!(vt.get_u16s(0x0000, 5), [0x100, 0x100, 0x101, 0x102, 0x103]);
+            vt.set_u16s(0x0000, vt.get_u16s(0x0001, 4));
+            assert_eq!(vt.get_u16s(0x0000, 5), [0x100, 0x101, 0x102, 0x103, 0x103]);
+        }
+        let vt = ValueTable::new_tracked();
+        vt.set_u16s(0x0000, &[1, 2]);
+        vt.drain_dirty();
+        vt.set_u16s(0x0001, vt.get_u16s(0x0000, 2));
+        assert_eq!(vt.drain_dirty(), [(Region::Bits16, 1), (Region::Bits16, 2)]);
+    }
+
+    #[test]
+    fn copy_values() {
+        let vt = ValueTable::new();
+        vt.set_u16s(0xFFFD, &[1, 2, 3]);
+        let vals = vt.copy_u16s(0xFFFD, 8);
+        vt.set_u16(0xFFFD, 9);
+        assert_eq!(vals, [1, 2, 3]);
+        assert!(vt.copy_u16s(0x0000, 0).is_empty());
+        vt.set_f32s(0x0010, &[1.5, -2.0]);
+        assert_eq!(vt.copy_f32s(0x0010, 2), [1.5, -2.0]);
+        assert_eq!(vt.copy_u128s(0xFFFF, 2).len(), 1);
+    }
 
     #[test]
     fn bits() {
         let regs = ValueTable::new();
         for a in u16::MIN..=u16::MAX {
             regs.set_bit(a);
-            assert_eq!(regs.get_bit(a), true);
+            assert!(regs.get_bit(a));
             regs.clear_bit(a);
-            assert_eq!(regs.get_bit(a), false);
+            assert!(!regs.get_bit(a));
+        }
+    }
+
+    #[test]
+    fn bits_distinct() {
+        let regs = ValueTable::new();
+        for a in (u16::MIN..=u16::MAX).step_by(2) {
+            regs.set_bit(a);
+        }
+        for a in u16::MIN..=u16::MAX {
+            assert_eq!(regs.get_bit(a), a % 2 == 0);
         }
+        assert!(regs.region_bytes(Region::Bits8).iter().all(|b| *b == 0));
     }
 
     #[test]
@@ -161,6 +888,53 @@ mod tests {
             assert_eq!(regs.get_u8(a), 0xAA);
         }
     }
+
+    #[test]
+    fn floats() {
+        let regs = ValueTable::new();
+        let f32s = [1.5f32, -0.0, f32::NAN, f32::INFINITY, f32::NEG_INFINITY];
+        let f64s = [1.5f64, -0.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY];
+        for a in u16::MIN..=u16::MAX {
+            let v = f32s[a as usize % f32s.len()];
+            regs.set_f32(a, v);
+            assert_eq!(regs.get_f32(a).to_bits(), v.to_bits());
+            assert_eq!(regs.get_u32(a), v.to_bits());
+            let v = f64s[a as usize % f64s.len()];
+            regs.set_f64(a, v);
+            assert_eq!(regs.get_f64(a).to_bits(), v.to_bits());
+            assert_eq!(regs.get_u64(a), v.to_bits());
+        }
+        let nan = f32::from_bits(0x7FC0_1234);
+        regs.set_f32s(0xFFFE, &[nan, 2.0, 3.0]);
+        assert_eq!(regs.get_u32(0xFFFE), 0x7FC0_1234);
+        let vals = regs.get_f32s(0xFFFE, 3);
+        assert_eq!(vals.len(), 2);
+        assert_eq!(vals[0].to_bits(), nan.to_bits());
+        assert_eq!(vals[1], 2.0);
+    }
+
+    #[test]
+    fn region_boundaries() {
+        let regs = ValueTable::new();
+        assert_eq!(regs.set_u8s(0xFFFE, &[0xFF; 4]), 2);
+        assert_eq!(regs.set_u16s(0xFFFE, &[0xFFFF; 4]), 2);
+        assert_eq!(regs.set_u32s(0xFFFE, &[u32::MAX; 4]), 2);
+        assert_eq!(regs.set_u64s(0xFFFE, &[u64::MAX; 4]), 2);
+        assert_eq!(regs.set_u16s(0x0000, &[]), 0);
+        assert_eq!(regs.get_u8s(0xFFFE, 4), &[0xFF; 2]);
+        assert_eq!(regs.get_u64s(0xFFFF, 4), &[u64::MAX]);
+
+        assert_eq!(regs.get_u16(0x0000), 0);
+        assert_eq!(regs.get_u32(0x0000), 0);
+        assert_eq!(regs.get_u64(0x0000), 0);
+        let tail = unsafe {
+            core::slice::from_raw_parts(
+                regs.mem.as_ptr().add(ValueTable::BITS_128_REG_END),
+                ValueTable::BUFFER_SIZE - ValueTable::BITS_128_REG_END,
+            )
+        };
+        assert!(tail.iter().all(|b| *b == 0));
+    }
 }
 
 // fn main() {
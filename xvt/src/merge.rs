@@ -0,0 +1,255 @@
+use crate::{Region, ValueTable};
+
+/// 一个代表合并两个存储表时冲突处理方式的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// 所有值都取另一个存储表的值。
+    PreferOther,
+    /// 保留本存储表中的非零值，为零时取另一个存储表的值；单比特区域相当于按位或。
+    PreferSelfIfNonZero,
+    /// 按位或合并，单比特区域取两者置位的并集，数值区域对两个值的存储字节逐位求或。
+    BitwiseOr,
+}
+
+impl MergePolicy {
+    /// 按该方式合并一个值的存储字节，`cur` 为本存储表的值，结果写回 `cur`。
+    fn merge(self, cur: &mut [u8], other: &[u8]) {
+        match self {
+            MergePolicy::PreferOther => cur.copy_from_slice(other),
+            MergePolicy::PreferSelfIfNonZero => {
+                if cur.iter().all(|b| *b == 0) {
+                    cur.copy_from_slice(other);
+                }
+            }
+            MergePolicy::BitwiseOr => cur.iter_mut().zip(other).for_each(|(c, o)| *c |= o),
+        }
+    }
+}
+
+impl ValueTable {
+    /// 按方式 `policy` 将 `other` 中的值合并到本存储表。
+    ///
+    /// 两个存储表的布局不同时，只合并双方都能容纳的部分。值发生变化时会像 `set_*` 系列一样
+    /// 触发回调和脏标记。
+    pub fn merge_from(&self, other: &ValueTable, policy: MergePolicy) {
+        if core::ptr::eq(self, other) {
+            return;
+        }
+        const W: usize = core::mem::size_of::<usize>();
+
+        let words = self
+            .region_len(Region::Bit)
+            .min(other.region_len(Region::Bit))
+            / W;
+        let src = other.region_bytes(Region::Bit).chunks_exact(W);
+        let dst_ptr = self.region_ptr(Region::Bit).cast::<usize>();
+        for (i, w) in src.enumerate().take(words) {
+            let o = usize::from_ne_bytes(w.try_into().unwrap());
+            let old = unsafe { *dst_ptr.add(i) };
+            let new = match policy {
+                MergePolicy::PreferOther => o,
+                MergePolicy::PreferSelfIfNonZero | MergePolicy::BitwiseOr => old | o,
+            };
+            let mut x = old ^ new;
+            if x == 0 {
+                continue;
+            }
+            unsafe { *dst_ptr.add(i) = new };
+            while x != 0 && self.hooked() {
+                let addr = i * W * 8 + x.trailing_zeros() as usize;
+                self.on_written(Region::Bit, addr as u16, true);
+                x &= x - 1;
+            }
+        }
+
+        for region in Region::VALUES {
+            let n = region.element_size();
+            let len = self.region_len(region).min(other.region_len(region));
+            let src = &other.region_bytes(region)[..len];
+            let dst_ptr = self.region_ptr(region);
+            let mut val = [0u8; 16];
+            for (addr, o) in src.chunks_exact(n).enumerate() {
+                let cur = unsafe { core::slice::from_raw_parts_mut(dst_ptr.add(addr * n), n) };
+                val[..n].copy_from_slice(cur);
+                policy.merge(cur, o);
+                if cur != &val[..n] {
+                    self.on_written(region, addr as u16, true);
+                }
+            }
+        }
+    }
+}
+
+impl ValueTable {
+    /// 以 `op` 逐字合并 `other` 的单比特区域，`other` 容量之外的位视为 `0`。
+    fn bit_assign(&self, other: &ValueTable, op: impl Fn(usize, usize) -> usize) {
+        const W: usize = core::mem::size_of::<usize>();
+        let words = self.region_len(Region::Bit) / W;
+        let other_words = other.region_len(Region::Bit) / W;
+        let dst_ptr = self.region_ptr(Region::Bit).cast::<usize>();
+        let src_ptr = other.region_ptr(Region::Bit).cast::<usize>();
+        for i in 0..words {
+            let o = if i < other_words {
+                unsafe { src_ptr.add(i).read() }
+            } else {
+                0
+            };
+            let old = unsafe { dst_ptr.add(i).read() };
+            let new = op(old, o);
+            let mut x = old ^ new;
+            if x == 0 {
+                continue;
+            }
+            unsafe { dst_ptr.add(i).write(new) };
+            while x != 0 && self.hooked() {
+                let addr = i * W * 8 + x.trailing_zeros() as usize;
+                self.on_written(Region::Bit, addr as u16, true);
+                x &= x - 1;
+            }
+        }
+    }
+
+    /// 将单比特区域按位或上 `other` 的单比特区域，即取两者置位的并集。
+    ///
+    /// 逐字处理整个区域；值发生变化的位会触发回调和脏标记，下同。
+    pub fn bit_or_assign(&self, other: &ValueTable) {
+        self.bit_assign(other, |a, b| a | b);
+    }
+
+    /// 将单比特区域按位与上 `other` 的单比特区域，即取两者置位的交集。
+    ///
+    /// 超出 `other` 容量的位被清除。
+    pub fn bit_and_assign(&self, other: &ValueTable) {
+        self.bit_assign(other, |a, b| a & b);
+    }
+
+    /// 将单比特区域按位异或上 `other` 的单比特区域，即取两者置位的对称差。
+    pub fn bit_xor_assign(&self, other: &ValueTable) {
+        self.bit_assign(other, |a, b| a ^ b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RegionConfig;
+    use alloc::vec::Vec;
+
+    fn tables() -> (ValueTable, ValueTable) {
+        let live = ValueTable::new();
+        live.set_bit(0x0001);
+        live.set_bit(0x0100);
+        live.set_u16(0x0010, 0x00F0);
+        live.set_f32(0x0020, 1.5);
+
+        let defaults = ValueTable::new();
+        defaults.set_bit(0x0001);
+        defaults.set_bit(0x0002);
+        defaults.set_u16(0x0010, 0x0F0F);
+        defaults.set_u16(0x0011, 7);
+        defaults.set_f32(0x0020, 2.5);
+        defaults.set_u128(0xFFFF, 1);
+        (live, defaults)
+    }
+
+    #[test]
+    fn prefer_other() {
+        let (live, defaults) = tables();
+        live.merge_from(&defaults, MergePolicy::PreferOther);
+        assert_eq!(live.as_bytes(), defaults.as_bytes());
+    }
+
+    #[test]
+    fn prefer_self_if_non_zero() {
+        let (live, defaults) = tables();
+        live.merge_from(&defaults, MergePolicy::PreferSelfIfNonZero);
+        assert_eq!(live.iter_set_bits().collect::<Vec<_>>(), [1, 2, 0x0100]);
+        assert_eq!(live.get_u16s(0x0010, 2), &[0x00F0, 7]);
+        assert_eq!(live.get_f32(0x0020), 1.5);
+        assert_eq!(live.get_u128(0xFFFF), 1);
+    }
+
+    #[test]
+    fn bitwise_or() {
+        let (live, defaults) = tables();
+        live.merge_from(&defaults, MergePolicy::BitwiseOr);
+        assert_eq!(live.iter_set_bits().collect::<Vec<_>>(), [1, 2, 0x0100]);
+        assert_eq!(live.get_u16s(0x0010, 2), &[0x0FFF, 7]);
+        assert_eq!(
+            live.get_f32(0x0020).to_bits(),
+            1.5f32.to_bits() | 2.5f32.to_bits()
+        );
+    }
+
+    #[test]
+    fn marks_changes() {
+        let (_, defaults) = tables();
+        let live = ValueTable::new_tracked();
+        live.set_bit(0x0001);
+        live.set_bit(0x0100);
+        live.set_u16(0x0010, 0x00F0);
+        live.set_f32(0x0020, 1.5);
+        live.drain_dirty();
+        live.merge_from(&defaults, MergePolicy::PreferSelfIfNonZero);
+        assert_eq!(
+            live.drain_dirty(),
+            [
+                (Region::Bit, 0x0002),
+                (Region::Bits16, 0x0011),
+                (Region::Bits128, 0xFFFF),
+            ]
+        );
+    }
+
+    #[test]
+    fn smaller_layout() {
+        let small = ValueTable::with_regions(RegionConfig {
+            bits: 64,
+            bits16: 2,
+            ..RegionConfig::empty()
+        });
+        let (_, defaults) = tables();
+        small.merge_from(&defaults, MergePolicy::PreferOther);
+        assert_eq!(small.iter_set_bits().collect::<Vec<_>>(), [1, 2]);
+        assert!(small.get_u16s(0x0000, 2).iter().all(|v| *v == 0));
+        defaults.merge_from(&small, MergePolicy::PreferOther);
+        assert_eq!(defaults.get_u16(0x0011), 7);
+    }
+
+    #[test]
+    fn bit_ops() {
+        let pattern = |bits: &[u16]| {
+            let vt = ValueTable::new();
+            vt.set_bits(bits.iter().copied());
+            vt
+        };
+        let a = [1, 2, 0x0100, 0xFFFF];
+        let b = [2, 3, 0x0100];
+
+        let vt = pattern(&a);
+        vt.bit_or_assign(&pattern(&b));
+        assert_eq!(
+            vt.iter_set_bits().collect::<Vec<_>>(),
+            [1, 2, 3, 0x0100, 0xFFFF]
+        );
+
+        let vt = pattern(&a);
+        vt.bit_and_assign(&pattern(&b));
+        assert_eq!(vt.iter_set_bits().collect::<Vec<_>>(), [2, 0x0100]);
+
+        let vt = pattern(&a);
+        vt.bit_xor_assign(&pattern(&b));
+        assert_eq!(vt.iter_set_bits().collect::<Vec<_>>(), [1, 3, 0xFFFF]);
+        vt.bit_xor_assign(&vt);
+        assert_eq!(vt.iter_set_bits().count(), 0);
+
+        let small = ValueTable::with_regions(RegionConfig {
+            bits: 64,
+            ..RegionConfig::empty()
+        });
+        small.set_bits([1, 2, 5]);
+        let vt = pattern(&a);
+        vt.bit_and_assign(&small);
+        assert_eq!(vt.iter_set_bits().collect::<Vec<_>>(), [1, 2]);
+    }
+}
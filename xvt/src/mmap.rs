@@ -0,0 +1,80 @@
+use crate::{Backing, RegionConfig, RegionLayout, ValueTable};
+use memmap2::MmapMut;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::ptr::NonNull;
+
+impl ValueTable {
+    /// 打开或创建文件 `path`，构建一个以该文件映射内存为存储的多种值存储表实例。
+    ///
+    /// 各区域按 [`RegionConfig::full`] 布局，新建的文件会被扩展为存储表大小；已有文件的大小必须与存储表大小一致。
+    /// 存储表被释放时会将内容同步到文件中。
+    pub fn open_mmap(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let len = file.metadata()?.len();
+        if len == 0 {
+            file.set_len(Self::BUFFER_SIZE as u64)?;
+        } else if len != Self::BUFFER_SIZE as u64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("文件大小 {} 与存储表大小 {} 不符", len, Self::BUFFER_SIZE),
+            ));
+        }
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        let mem = NonNull::new(mmap.as_mut_ptr()).unwrap();
+        Ok(Self {
+            mem,
+            layout: RegionLayout::new(RegionConfig::full()),
+            observers: Default::default(),
+            dirty: None,
+            presence: None,
+            version: Default::default(),
+            generations: Default::default(),
+            zeroize: false,
+            backing: Backing::Mmap(mmap),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn reopen() {
+        let path = std::env::temp_dir().join(format!("xvt-mmap-{}.bin", std::process::id()));
+        let _ = fs::remove_file(&path);
+        {
+            let vt = ValueTable::open_mmap(&path).unwrap();
+            vt.set_bit(0x1234);
+            vt.set_u16(0x0010, 0xBEEF);
+            vt.set_f64(0xFFFF, 2.5);
+        }
+        assert_eq!(
+            fs::metadata(&path).unwrap().len(),
+            ValueTable::BUFFER_SIZE as u64
+        );
+        {
+            let vt = ValueTable::open_mmap(&path).unwrap();
+            assert!(vt.get_bit(0x1234));
+            assert!(!vt.get_bit(0x1235));
+            assert_eq!(vt.get_u16(0x0010), 0xBEEF);
+            assert_eq!(vt.get_f64(0xFFFF), 2.5);
+        }
+        fs::OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(16)
+            .unwrap();
+        assert!(ValueTable::open_mmap(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{Region, ValueTable};
+#[cfg(feature = "std")]
+use alloc::{sync::Arc, vec::Vec};
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicUsize;
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use std::sync::{Condvar, Mutex, RwLock};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// 值变化回调函数的类型。
+#[cfg(feature = "std")]
+pub type ChangeCallback = Arc<dyn Fn(Region, u16) + Send + Sync>;
+
+/// 存储表上注册的值变化回调。
+///
+/// 回调列表依赖 `std` 中的读写锁，未启用 `std` 特性时不支持注册回调。
+#[derive(Default)]
+pub(crate) struct Observers {
+    /// 是否注册了任何回调，用于在写入路径上快速跳过。
+    active: AtomicBool,
+    #[cfg(feature = "std")]
+    callbacks: RwLock<Vec<ChangeCallback>>,
+    /// 正在 [`ValueTable::wait_for_bit`] 中等待的线程数。
+    #[cfg(feature = "std")]
+    watching: AtomicUsize,
+    #[cfg(feature = "std")]
+    watch_lock: Mutex<()>,
+    #[cfg(feature = "std")]
+    watch_cv: Condvar,
+}
+
+impl Observers {
+    /// 是否有线程在等待单比特值变化。
+    #[inline]
+    fn watching(&self) -> bool {
+        #[cfg(feature = "std")]
+        return self.watching.load(Ordering::Acquire) != 0;
+        #[cfg(not(feature = "std"))]
+        false
+    }
+}
+
+impl ValueTable {
+    /// 注册一个值变化回调 `cb`。
+    ///
+    /// 每当 `set_*` 系列、`set_bit` 或 `clear_bit` 实际改变了某个值时，以该值所在的区域和地址调用 `cb`；
+    /// 写入相同的值不会触发回调。回调在写入线程上同步执行，复制出的存储表不继承已注册的回调。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use xvt::{Region, ValueTable};
+    ///
+    /// let vt = ValueTable::new();
+    /// let events = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = events.clone();
+    /// vt.on_change(Arc::new(move |region, addr| sink.lock().unwrap().push((region, addr))));
+    /// vt.set_u16(0x0010, 1);
+    /// vt.set_u16(0x0010, 1);
+    /// assert_eq!(*events.lock().unwrap(), [(Region::Bits16, 0x0010)]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn on_change(&self, cb: ChangeCallback) {
+        let mut callbacks = self
+            .observers
+            .callbacks
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        callbacks.push(cb);
+        self.observers.active.store(true, Ordering::Release);
+    }
+
+    /// 写入路径上是否需要逐个值地调用 [`ValueTable::on_written`]。
+    #[inline]
+    pub(crate) fn hooked(&self) -> bool {
+        self.dirty.is_some()
+            || self.presence.is_some()
+            || self.observers.active.load(Ordering::Acquire)
+            || self.generations.active()
+            || self.observers.watching()
+    }
+
+    /// 区域 `region` 中地址 `addr` 的值被写入后调用，`changed` 表示值是否实际发生了变化。
+    #[inline]
+    pub(crate) fn on_written(&self, region: Region, addr: u16, changed: bool) {
+        if let Some(dirty) = &self.dirty {
+            dirty.mark(region, addr);
+        }
+        if let Some(presence) = &self.presence {
+            presence.mark(region, addr);
+        }
+        if changed {
+            self.generations.bump(region);
+            if self.observers.active.load(Ordering::Acquire) {
+                self.notify_change(region, addr);
+            }
+            if region == Region::Bit && self.observers.watching() {
+                self.wake_watchers();
+            }
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn wake_watchers(&self) {}
+
+    #[cfg(feature = "std")]
+    #[cold]
+    fn wake_watchers(&self) {
+        // 持锁通知，等待方在检查值与进入等待之间不会错过这次变化。
+        let _guard = self
+            .observers
+            .watch_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        self.observers.watch_cv.notify_all();
+    }
+
+    /// 阻塞当前线程，直到地址 `addr` 的单比特值等于 `value` 或超过 `timeout`，返回是否等到了该值。
+    ///
+    /// 通过 `set_*` 系列、`set_bit` 或 `clear_bit` 改变单比特值时会立即唤醒等待方；原子操作、
+    /// 批量操作等不经过变化通知的写入则由每 10 毫秒一次的重新检查发现。超出区域容量的地址
+    /// 恒为 `false`。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use xvt::ValueTable;
+    ///
+    /// let vt = ValueTable::new();
+    /// vt.set_bit(0x0001);
+    /// assert!(vt.wait_for_bit(0x0001, true, Duration::ZERO));
+    /// assert!(!vt.wait_for_bit(0x0002, true, Duration::from_millis(1)));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_for_bit(&self, addr: u16, value: bool, timeout: Duration) -> bool {
+        const POLL: Duration = Duration::from_millis(10);
+        let deadline = Instant::now() + timeout;
+        let obs = &self.observers;
+        obs.watching.fetch_add(1, Ordering::AcqRel);
+        let mut guard = obs.watch_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let reached = loop {
+            if self.get_bit(addr) == value {
+                break true;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                break false;
+            }
+            let wait = (deadline - now).min(POLL);
+            guard = match obs.watch_cv.wait_timeout(guard, wait) {
+                Ok((g, _)) => g,
+                Err(e) => e.into_inner().0,
+            };
+        };
+        drop(guard);
+        obs.watching.fetch_sub(1, Ordering::AcqRel);
+        reached
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn notify_change(&self, _region: Region, _addr: u16) {}
+
+    #[cfg(feature = "std")]
+    #[cold]
+    fn notify_change(&self, region: Region, addr: u16) {
+        // 先复制回调列表再调用，回调中注册新的回调不会死锁。
+        let callbacks = self
+            .observers
+            .callbacks
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        for cb in callbacks {
+            cb(region, addr);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn recorder(vt: &ValueTable) -> Arc<Mutex<Vec<(Region, u16)>>> {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        vt.on_change(Arc::new(move |region, addr| {
+            sink.lock().unwrap().push((region, addr))
+        }));
+        events
+    }
+
+    #[test]
+    fn only_on_change() {
+        let vt = ValueTable::new();
+        let events = recorder(&vt);
+        vt.set_u16(0x0100, 7);
+        vt.set_u16(0x0100, 7);
+        vt.set_bit(0x0003);
+        vt.set_bit(0x0003);
+        vt.clear_bit(0x0004);
+        vt.clear_bit(0x0003);
+        vt.set_f32(0x0000, f32::NAN);
+        vt.set_f32(0x0000, f32::NAN);
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                (Region::Bits16, 0x0100),
+                (Region::Bit, 0x0003),
+                (Region::Bit, 0x0003),
+                (Region::Bits32, 0x0000),
+            ]
+        );
+    }
+
+    #[test]
+    fn slices() {
+        let vt = ValueTable::new();
+        vt.set_u8(0x0011, 2);
+        let events = recorder(&vt);
+        assert_eq!(vt.set_u8s(0x0010, &[1, 2, 3]), 3);
+        assert_eq!(vt.get_u8s(0x0010, 3), &[1, 2, 3]);
+        assert_eq!(
+            *events.lock().unwrap(),
+            [(Region::Bits8, 0x0010), (Region::Bits8, 0x0012)]
+        );
+        assert!(vt.clone().set_u8s(0x0010, &[9]) == 1);
+        assert_eq!(events.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn wait_for_bit() {
+        use std::thread;
+
+        let vt = Arc::new(ValueTable::new());
+        let setter = {
+            let vt = vt.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(20));
+                vt.set_bit(0x0042);
+            })
+        };
+        assert!(vt.wait_for_bit(0x0042, true, Duration::from_secs(10)));
+        setter.join().unwrap();
+        assert!(vt.wait_for_bit(0x0043, false, Duration::ZERO));
+        assert!(!vt.hooked());
+    }
+
+    #[test]
+    fn wait_for_bit_timeout() {
+        let vt = ValueTable::new();
+        let start = Instant::now();
+        assert!(!vt.wait_for_bit(0x0001, true, Duration::from_millis(30)));
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+}
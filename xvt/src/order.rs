@@ -0,0 +1,349 @@
+use crate::{Region, ValueTable, XvtError};
+use paste::paste;
+
+/// 一个代表多字节值在寄存器中存放顺序的类型。
+///
+/// 以 `0x11223344` 为例，各顺序在存储中的字节排列如下：
+///
+/// * `BigEndian` - `11 22 33 44`，高位字在前，字内高字节在前。
+/// * `LittleEndian` - `44 33 22 11`，低位字在前，字内低字节在前。
+/// * `BigEndianByteSwap` - `22 11 44 33`，高位字在前，字内低字节在前。
+/// * `LittleEndianByteSwap` - `33 44 11 22`，低位字在前，字内高字节在前。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// 大端字序。
+    BigEndian,
+    /// 小端字序。
+    LittleEndian,
+    /// 大端字序，字内字节交换。
+    BigEndianByteSwap,
+    /// 小端字序，字内字节交换。
+    LittleEndianByteSwap,
+}
+
+impl ByteOrder {
+    /// 在大端字节排列与该顺序的字节排列之间进行转换。
+    ///
+    /// 所有转换都是自逆的，因此同一函数可用于两个方向。
+    pub(crate) fn arrange(self, bytes: &mut [u8]) {
+        match self {
+            ByteOrder::BigEndian => {}
+            ByteOrder::LittleEndian => bytes.reverse(),
+            ByteOrder::BigEndianByteSwap => {
+                bytes.chunks_exact_mut(2).for_each(|w| w.swap(0, 1));
+            }
+            ByteOrder::LittleEndianByteSwap => {
+                bytes.reverse();
+                bytes.chunks_exact_mut(2).for_each(|w| w.swap(0, 1));
+            }
+        }
+    }
+}
+
+macro_rules! impl_endian_bytes {
+    ($t:ty, $n:expr) => {
+        paste! {
+            #[doc = "以大端字节序返回指定地址 `addr` 类型为 `" $t "` 的值的 " $n " 个字节。"]
+            pub fn [<get_ $t _be_bytes>](&self, addr: u16) -> [u8; $n] {
+                self.[<get_ $t>](addr).to_be_bytes()
+            }
+
+            #[doc = "以小端字节序返回指定地址 `addr` 类型为 `" $t "` 的值的 " $n " 个字节。"]
+            pub fn [<get_ $t _le_bytes>](&self, addr: u16) -> [u8; $n] {
+                self.[<get_ $t>](addr).to_le_bytes()
+            }
+        }
+    };
+}
+
+impl ValueTable {
+    impl_endian_bytes!(u16, 2);
+    impl_endian_bytes!(u32, 4);
+    impl_endian_bytes!(u64, 8);
+
+    /// 按字节顺序 `order` 解释指定地址 `addr` 类型为 `u32` 的值的存储字节。
+    pub fn get_u32_ordered(&self, addr: u16, order: ByteOrder) -> u32 {
+        let mut bytes = self.get_u32(addr).to_ne_bytes();
+        order.arrange(&mut bytes);
+        u32::from_be_bytes(bytes)
+    }
+
+    /// 按字节顺序 `order` 存储指定地址 `addr` 类型为 `u32` 的值。
+    pub fn set_u32_ordered(&self, addr: u16, val: u32, order: ByteOrder) {
+        let mut bytes = val.to_be_bytes();
+        order.arrange(&mut bytes);
+        self.set_u32(addr, u32::from_ne_bytes(bytes));
+    }
+
+    /// 按字节顺序 `order` 解释指定地址 `addr` 类型为 `u64` 的值的存储字节。
+    pub fn get_u64_ordered(&self, addr: u16, order: ByteOrder) -> u64 {
+        let mut bytes = self.get_u64(addr).to_ne_bytes();
+        order.arrange(&mut bytes);
+        u64::from_be_bytes(bytes)
+    }
+
+    /// 按字节顺序 `order` 存储指定地址 `addr` 类型为 `u64` 的值。
+    pub fn set_u64_ordered(&self, addr: u16, val: u64, order: ByteOrder) {
+        let mut bytes = val.to_be_bytes();
+        order.arrange(&mut bytes);
+        self.set_u64(addr, u64::from_ne_bytes(bytes));
+    }
+
+    /// 将 16 位区域中相邻的 `reg` 和 `reg + 1` 两个值按顺序 `order` 组合为一个 `u32` 值。
+    ///
+    /// 每个 16 位值视为一个大端寄存器，`order` 描述两个寄存器的 4 个字节的排列方式；
+    /// 两个值不完全位于区域内时返回 `0`。
+    pub fn get_u16_pair_as_u32(&self, reg: u16, order: ByteOrder) -> u32 {
+        let [a, b] = self.get_u16s(reg, 2) else {
+            return 0;
+        };
+        let (a, b) = (a.to_be_bytes(), b.to_be_bytes());
+        let mut bytes = [a[0], a[1], b[0], b[1]];
+        order.arrange(&mut bytes);
+        u32::from_be_bytes(bytes)
+    }
+
+    /// 将 `u32` 值 `val` 按顺序 `order` 拆分后存入 16 位区域中相邻的 `reg` 和 `reg + 1` 两个值。
+    ///
+    /// 两个值不完全位于区域内时不做任何操作。
+    pub fn set_u32_as_u16_pair(&self, reg: u16, val: u32, order: ByteOrder) {
+        if reg as usize + 2 > self.region_capacity(Region::Bits16) {
+            return;
+        }
+        let mut bytes = val.to_be_bytes();
+        order.arrange(&mut bytes);
+        self.set_u16(reg, u16::from_be_bytes([bytes[0], bytes[1]]));
+        self.set_u16(reg + 1, u16::from_be_bytes([bytes[2], bytes[3]]));
+    }
+
+    /// 将 16 位区域中从 `reg` 开始的 4 个相邻值按顺序 `order` 组合为一个 `u64` 值。
+    ///
+    /// 每个 16 位值视为一个大端寄存器，`order` 描述 4 个寄存器的 8 个字节的排列方式；
+    /// 4 个值不完全位于区域内时返回 `0`。
+    pub fn get_u64_from_u16_quad(&self, reg: u16, order: ByteOrder) -> u64 {
+        let regs = self.get_u16s(reg, 4);
+        if regs.len() < 4 {
+            return 0;
+        }
+        let mut bytes = [0u8; 8];
+        for (b, val) in bytes.chunks_exact_mut(2).zip(regs) {
+            b.copy_from_slice(&val.to_be_bytes());
+        }
+        order.arrange(&mut bytes);
+        u64::from_be_bytes(bytes)
+    }
+
+    /// 将 `u64` 值 `val` 按顺序 `order` 拆分后存入 16 位区域中从 `reg` 开始的 4 个相邻值。
+    ///
+    /// 4 个值不完全位于区域内时不做任何操作。
+    pub fn set_u64_as_u16_quad(&self, reg: u16, val: u64, order: ByteOrder) {
+        if reg as usize + 4 > self.region_capacity(Region::Bits16) {
+            return;
+        }
+        let mut bytes = val.to_be_bytes();
+        order.arrange(&mut bytes);
+        for (i, b) in bytes.chunks_exact(2).enumerate() {
+            self.set_u16(reg + i as u16, u16::from_be_bytes([b[0], b[1]]));
+        }
+    }
+
+    /// 检查从地址 `start` 开始的 `count` 个 `u16` 值是否位于区域内，且字节数为 `len`。
+    fn check_be_block(&self, start: u16, count: u16, len: usize) -> Result<(), XvtError> {
+        if start as usize + count as usize > self.region_capacity(Region::Bits16) {
+            return Err(XvtError::OutOfRange);
+        }
+        if len != count as usize * 2 {
+            return Err(XvtError::LengthMismatch {
+                expected: count as usize * 2,
+                actual: len,
+            });
+        }
+        Ok(())
+    }
+
+    /// 将从地址 `start` 开始的 `count` 个类型为 `u16` 的值以大端字节序写入 `out`。
+    ///
+    /// 范围超出区域时返回 [`XvtError::OutOfRange`]，`out` 的长度不是 `count * 2` 时返回
+    /// [`XvtError::LengthMismatch`]，出错时 `out` 保持不变。
+    pub fn read_be_block(&self, start: u16, count: u16, out: &mut [u8]) -> Result<(), XvtError> {
+        self.check_be_block(start, count, out.len())?;
+        for (b, val) in out.chunks_exact_mut(2).zip(self.get_u16s(start, count)) {
+            b.copy_from_slice(&val.to_be_bytes());
+        }
+        Ok(())
+    }
+
+    /// 将大端字节序的 `data` 作为 `count` 个类型为 `u16` 的值写入从地址 `start` 开始的位置。
+    ///
+    /// 范围超出区域时返回 [`XvtError::OutOfRange`]，`data` 的长度不是 `count * 2` 时返回
+    /// [`XvtError::LengthMismatch`]，出错时存储表保持不变。
+    pub fn write_be_block(&self, start: u16, count: u16, data: &[u8]) -> Result<(), XvtError> {
+        self.check_be_block(start, count, data.len())?;
+        for (i, b) in data.chunks_exact(2).enumerate() {
+            self.set_u16(start + i as u16, u16::from_be_bytes([b[0], b[1]]));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORDERS: [ByteOrder; 4] = [
+        ByteOrder::BigEndian,
+        ByteOrder::LittleEndian,
+        ByteOrder::BigEndianByteSwap,
+        ByteOrder::LittleEndianByteSwap,
+    ];
+
+    #[test]
+    fn endian_bytes() {
+        let vt = ValueTable::new();
+        vt.set_u16(0x0001, 0x1234);
+        vt.set_u32(0x0001, 0x1234_5678);
+        vt.set_u64(0xFFFF, 0x0102_0304_0506_0708);
+        assert_eq!(vt.get_u16_be_bytes(0x0001), [0x12, 0x34]);
+        assert_eq!(vt.get_u16_le_bytes(0x0001), [0x34, 0x12]);
+        assert_eq!(vt.get_u32_be_bytes(0x0001), [0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(vt.get_u32_le_bytes(0x0001), [0x78, 0x56, 0x34, 0x12]);
+        assert_eq!(vt.get_u64_be_bytes(0xFFFF), [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(vt.get_u64_le_bytes(0xFFFF), [8, 7, 6, 5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn u16_pair() {
+        let vt = ValueTable::new();
+        let regs = [
+            [0x1122, 0x3344],
+            [0x4433, 0x2211],
+            [0x2211, 0x4433],
+            [0x3344, 0x1122],
+        ];
+        for (order, pair) in ORDERS.iter().zip(regs) {
+            vt.set_u32_as_u16_pair(0x0010, 0x1122_3344, *order);
+            assert_eq!(vt.get_u16s(0x0010, 2), &pair);
+            assert_eq!(vt.get_u16_pair_as_u32(0x0010, *order), 0x1122_3344);
+        }
+        assert_eq!(vt.get_u32(0x0010), 0);
+
+        vt.set_u32_as_u16_pair(0xFFFE, 0xDEAD_BEEF, ByteOrder::BigEndian);
+        assert_eq!(vt.get_u16s(0xFFFE, 2), &[0xDEAD, 0xBEEF]);
+        assert_eq!(
+            vt.get_u16_pair_as_u32(0xFFFE, ByteOrder::LittleEndianByteSwap),
+            0xBEEF_DEAD
+        );
+        vt.set_u32_as_u16_pair(0xFFFF, 0x0102_0304, ByteOrder::BigEndian);
+        assert_eq!(vt.get_u16(0xFFFF), 0xBEEF);
+        assert_eq!(vt.get_u16(0x0000), 0);
+        assert_eq!(vt.get_u16_pair_as_u32(0xFFFF, ByteOrder::BigEndian), 0);
+    }
+
+    #[test]
+    fn u16_quad() {
+        let vt = ValueTable::new();
+        let regs = [
+            [0x0102, 0x0304, 0x0506, 0x0708],
+            [0x0807, 0x0605, 0x0403, 0x0201],
+            [0x0201, 0x0403, 0x0605, 0x0807],
+            [0x0708, 0x0506, 0x0304, 0x0102],
+        ];
+        for (order, quad) in ORDERS.iter().zip(regs) {
+            vt.set_u64_as_u16_quad(0x0010, 0x0102_0304_0506_0708, *order);
+            assert_eq!(vt.get_u16s(0x0010, 4), &quad);
+            assert_eq!(
+                vt.get_u64_from_u16_quad(0x0010, *order),
+                0x0102_0304_0506_0708
+            );
+        }
+
+        vt.set_u64_as_u16_quad(0xFFFC, 0x0102_0304_0506_0708, ByteOrder::BigEndian);
+        assert_eq!(vt.get_u16s(0xFFFC, 4), &regs[0]);
+        assert_eq!(
+            vt.get_u64_from_u16_quad(0xFFFC, ByteOrder::BigEndian),
+            0x0102_0304_0506_0708
+        );
+        vt.set_u64_as_u16_quad(0xFFFD, u64::MAX, ByteOrder::BigEndian);
+        assert_eq!(vt.get_u16s(0xFFFC, 4), &regs[0]);
+        assert_eq!(vt.get_u16(0x0000), 0);
+        assert_eq!(vt.get_u64_from_u16_quad(0xFFFD, ByteOrder::BigEndian), 0);
+    }
+
+    #[test]
+    fn be_block() {
+        let vt = ValueTable::new();
+        vt.set_u16s(0xFFFD, &[0xBEEF, 0x0102, 0xFF00]);
+        let mut out = [0u8; 6];
+        assert_eq!(vt.read_be_block(0xFFFD, 3, &mut out), Ok(()));
+        assert_eq!(out, [0xBE, 0xEF, 0x01, 0x02, 0xFF, 0x00]);
+        assert_eq!(
+            vt.read_be_block(0xFFFE, 3, &mut out),
+            Err(XvtError::OutOfRange)
+        );
+        assert_eq!(
+            vt.read_be_block(0x0000, 2, &mut out),
+            Err(XvtError::LengthMismatch {
+                expected: 4,
+                actual: 6
+            })
+        );
+
+        assert_eq!(
+            vt.write_be_block(0x0010, 2, &[0xBE, 0xEF, 0x12, 0x34]),
+            Ok(())
+        );
+        assert_eq!(vt.get_u16s(0x0010, 2), &[0xBEEF, 0x1234]);
+        assert_eq!(
+            vt.write_be_block(0xFFFF, 2, &[0, 1, 0, 2]),
+            Err(XvtError::OutOfRange)
+        );
+        assert_eq!(vt.get_u16(0xFFFF), 0xFF00);
+        assert_eq!(vt.write_be_block(0x0000, 0, &[]), Ok(()));
+    }
+
+    #[test]
+    fn u32_orders() {
+        let vt = ValueTable::new();
+        let layouts = [
+            [0x11, 0x22, 0x33, 0x44],
+            [0x44, 0x33, 0x22, 0x11],
+            [0x22, 0x11, 0x44, 0x33],
+            [0x33, 0x44, 0x11, 0x22],
+        ];
+        for (order, bytes) in ORDERS.iter().zip(layouts) {
+            vt.set_u32_ordered(0x0010, 0x1122_3344, *order);
+            assert_eq!(vt.get_u32(0x0010).to_ne_bytes(), bytes);
+            assert_eq!(vt.get_u32_ordered(0x0010, *order), 0x1122_3344);
+        }
+
+        vt.set_u32_ordered(0x0010, 0x1122_3344, ByteOrder::BigEndian);
+        assert_eq!(
+            vt.get_u32_ordered(0x0010, ByteOrder::LittleEndian),
+            0x4433_2211
+        );
+        assert_eq!(
+            vt.get_u32_ordered(0x0010, ByteOrder::BigEndianByteSwap),
+            0x2211_4433
+        );
+        assert_eq!(
+            vt.get_u32_ordered(0x0010, ByteOrder::LittleEndianByteSwap),
+            0x3344_1122
+        );
+    }
+
+    #[test]
+    fn u64_orders() {
+        let vt = ValueTable::new();
+        let layouts = [
+            [1, 2, 3, 4, 5, 6, 7, 8],
+            [8, 7, 6, 5, 4, 3, 2, 1],
+            [2, 1, 4, 3, 6, 5, 8, 7],
+            [7, 8, 5, 6, 3, 4, 1, 2],
+        ];
+        for (order, bytes) in ORDERS.iter().zip(layouts) {
+            vt.set_u64_ordered(0xFFFF, 0x0102_0304_0506_0708, *order);
+            assert_eq!(vt.get_u64(0xFFFF).to_ne_bytes(), bytes);
+            assert_eq!(vt.get_u64_ordered(0xFFFF, *order), 0x0102_0304_0506_0708);
+        }
+    }
+}
@@ -0,0 +1,455 @@
+use crate::{Region, ValueTable, XvtError};
+use alloc::{vec, vec::Vec};
+
+/// 补丁帧头长度：单比特条目数与数值条目数各占 4 字节。
+const HEADER_LEN: usize = 8;
+
+/// 异或游程中单个字的字节数。
+const WORD_LEN: usize = 8;
+
+/// 以小端字节序追加一个值的原始字节。
+fn push_le(out: &mut Vec<u8>, bytes: &[u8]) {
+    if cfg!(target_endian = "big") {
+        out.extend(bytes.iter().rev());
+    } else {
+        out.extend_from_slice(bytes);
+    }
+}
+
+/// 以主机字节序写入一个小端编码的值。
+fn write_le(dst: *mut u8, bytes: &[u8]) {
+    unsafe {
+        for (i, b) in bytes.iter().enumerate() {
+            let i = if cfg!(target_endian = "big") {
+                bytes.len() - 1 - i
+            } else {
+                i
+            };
+            dst.add(i).write(*b);
+        }
+    }
+}
+
+/// 单比特区域中第 `i` 个字节（按小端字序，即包含地址 `i * 8` 至 `i * 8 + 7` 的字节）
+/// 在主机缓冲区中的位置。
+fn bit_byte_pos(i: usize) -> usize {
+    const W: usize = core::mem::size_of::<usize>();
+    if cfg!(target_endian = "big") {
+        i / W * W + (W - 1 - i % W)
+    } else {
+        i
+    }
+}
+
+/// 从补丁数据中依次读取字段的游标。
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], XvtError> {
+        if self.buf.len() < n {
+            return Err(XvtError::MalformedPatch);
+        }
+        let (head, tail) = self.buf.split_at(n);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, XvtError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, XvtError> {
+        Ok(u16::from_le_bytes([self.u8()?, self.u8()?]))
+    }
+
+    fn u32(&mut self) -> Result<u32, XvtError> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+/// 解码后的补丁条目。
+enum Entry<'a> {
+    /// 从第 `word` 个字开始的异或游程。
+    Xor { word: usize, mask: &'a [u8] },
+    /// 数值区域中单个地址的新值（小端字节序）。
+    Value {
+        region: Region,
+        addr: u16,
+        bytes: &'a [u8],
+    },
+}
+
+impl ValueTable {
+    /// 生成相对于基准表 `since` 的合并补丁。
+    ///
+    /// 单比特区域的变化以异或游程条目记录，数值区域的变化以
+    /// `(区域, 地址, 值)` 条目记录，两者共用一个帧。
+    ///
+    /// # 帧格式
+    ///
+    /// 所有多字节字段均为小端字节序。
+    ///
+    /// * 帧头 - 单比特条目数 `u32`，数值条目数 `u32`。
+    /// * 单比特条目 - 起始字序号 `u16`，字数 `u16`，随后为每个字 8 字节的异或掩码。掩码按小端字节序排列，
+    ///   第 `w` 个字的第 `k` 个字节的第 `b` 位对应地址 `(w * 8 + k) * 8 + b`，与主机字节序无关。
+    /// * 数值条目 - 区域标识 `u8`，地址 `u16`，随后为该区域宽度的值。
+    pub fn to_combined_patch(&self, since: &ValueTable) -> Vec<u8> {
+        let mut out = vec![0u8; HEADER_LEN];
+
+        // 按小端字序取字节，与主机字节序无关。
+        let cur = self.region_bytes(Region::Bit);
+        let old = since.region_bytes(Region::Bit);
+        let words = cur.len().min(old.len()) / WORD_LEN;
+        let xors: Vec<[u8; WORD_LEN]> = (0..words)
+            .map(|w| {
+                let mut x = [0u8; WORD_LEN];
+                for (k, b) in x.iter_mut().enumerate() {
+                    let pos = bit_byte_pos(w * WORD_LEN + k);
+                    *b = cur[pos] ^ old[pos];
+                }
+                x
+            })
+            .collect();
+        let mut bit_entries = 0u32;
+        let mut i = 0;
+        while i < xors.len() {
+            if xors[i] == [0; WORD_LEN] {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < xors.len() && xors[i] != [0; WORD_LEN] {
+                i += 1;
+            }
+            out.extend_from_slice(&(start as u16).to_le_bytes());
+            out.extend_from_slice(&((i - start) as u16).to_le_bytes());
+            for x in &xors[start..i] {
+                out.extend_from_slice(x);
+            }
+            bit_entries += 1;
+        }
+
+        let mut value_entries = 0u32;
+        for region in Region::VALUES {
+            let n = region.element_size();
+            let cur = self.region_bytes(region).chunks_exact(n);
+            let old = since.region_bytes(region).chunks_exact(n);
+            for (addr, (a, b)) in cur.zip(old).enumerate() {
+                if a != b {
+                    out.push(region as u8);
+                    out.extend_from_slice(&(addr as u16).to_le_bytes());
+                    push_le(&mut out, a);
+                    value_entries += 1;
+                }
+            }
+        }
+
+        out[0..4].copy_from_slice(&bit_entries.to_le_bytes());
+        out[4..8].copy_from_slice(&value_entries.to_le_bytes());
+        out
+    }
+
+    /// 应用由 [`ValueTable::to_combined_patch`] 生成的合并补丁。
+    ///
+    /// 补丁在写入前会被完整校验，格式错误时存储表保持不变。
+    pub fn apply_combined_patch(&self, patch: &[u8]) -> Result<(), XvtError> {
+        let words = self.region_len(Region::Bit) / WORD_LEN;
+        let mut rd = Reader { buf: patch };
+        let bit_entries = rd.u32()?;
+        let value_entries = rd.u32()?;
+        let mut entries = Vec::new();
+        for _ in 0..bit_entries {
+            let word = rd.u16()? as usize;
+            let count = rd.u16()? as usize;
+            if count == 0 || word + count > words {
+                return Err(XvtError::MalformedPatch);
+            }
+            let mask = rd.take(count * WORD_LEN)?;
+            entries.push(Entry::Xor { word, mask });
+        }
+        for _ in 0..value_entries {
+            let region = match Region::from_id(rd.u8()?) {
+                Some(Region::Bit) | None => return Err(XvtError::MalformedPatch),
+                Some(region) => region,
+            };
+            let addr = rd.u16()?;
+            if addr as usize >= self.region_capacity(region) {
+                return Err(XvtError::MalformedPatch);
+            }
+            let bytes = rd.take(region.element_size())?;
+            entries.push(Entry::Value {
+                region,
+                addr,
+                bytes,
+            });
+        }
+        if !rd.buf.is_empty() {
+            return Err(XvtError::MalformedPatch);
+        }
+
+        for entry in entries {
+            match entry {
+                Entry::Xor { word, mask } => unsafe {
+                    let dst = self.region_ptr(Region::Bit);
+                    for (i, m) in mask.iter().enumerate() {
+                        *dst.add(bit_byte_pos(word * WORD_LEN + i)) ^= m;
+                    }
+                    self.generations.bump(Region::Bit);
+                },
+                Entry::Value {
+                    region,
+                    addr,
+                    bytes,
+                } => unsafe {
+                    let dst = self
+                        .region_ptr(region)
+                        .add(addr as usize * region.element_size());
+                    write_le(dst, bytes);
+                    self.generations.bump(region);
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// 生成相对于快照 `since` 的增量补丁，连续变化的地址合并为一条记录。
+    ///
+    /// 快照长度与存储表大小不符时，缺失部分视为零。单比特区域以字节为单位比较，
+    /// 每个字节包含 8 个相邻地址。
+    ///
+    /// # 帧格式
+    ///
+    /// 补丁由若干条记录依次组成，所有多字节字段均为小端字节序。每条记录为区域标识 `u8`、
+    /// 起始地址 `u16`、值个数 `u16`，随后为各个值。单比特区域的地址与个数均以字节计。
+    pub fn encode_patch(&self, since: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for &region in Region::ALL {
+            let ofs = self.region_offset(region);
+            let cur = self.region_bytes(region);
+            let old = |i: usize| since.get(ofs + i).copied().unwrap_or(0);
+            let (n, count) = match region {
+                Region::Bit => (1, cur.len()),
+                _ => (region.element_size(), cur.len() / region.element_size()),
+            };
+            let pos = |addr: usize| match region {
+                Region::Bit => bit_byte_pos(addr),
+                _ => addr * n,
+            };
+            let changed = |addr: usize| (0..n).any(|k| cur[pos(addr) + k] != old(pos(addr) + k));
+
+            let mut addr = 0;
+            while addr < count {
+                if !changed(addr) {
+                    addr += 1;
+                    continue;
+                }
+                let start = addr;
+                while addr < count && addr - start < u16::MAX as usize && changed(addr) {
+                    addr += 1;
+                }
+                out.push(region as u8);
+                out.extend_from_slice(&(start as u16).to_le_bytes());
+                out.extend_from_slice(&((addr - start) as u16).to_le_bytes());
+                for a in start..addr {
+                    push_le(&mut out, &cur[pos(a)..pos(a) + n]);
+                }
+            }
+        }
+        out
+    }
+
+    /// 应用由 [`ValueTable::encode_patch`] 生成的增量补丁。
+    ///
+    /// 补丁在写入前会被完整校验，格式错误或记录超出区域容量时返回
+    /// [`XvtError::MalformedPatch`]，存储表保持不变。
+    pub fn apply_patch(&self, patch: &[u8]) -> Result<(), XvtError> {
+        let mut rd = Reader { buf: patch };
+        let mut records = Vec::new();
+        while !rd.buf.is_empty() {
+            let region = Region::from_id(rd.u8()?).ok_or(XvtError::MalformedPatch)?;
+            let start = rd.u16()? as usize;
+            let count = rd.u16()? as usize;
+            let (n, cap) = match region {
+                Region::Bit => (1, self.region_len(region)),
+                _ => (region.element_size(), self.region_capacity(region)),
+            };
+            if count == 0 || start + count > cap {
+                return Err(XvtError::MalformedPatch);
+            }
+            let bytes = rd.take(count * n)?;
+            records.push((region, start, n, bytes));
+        }
+
+        for (region, start, n, bytes) in records {
+            let base = self.region_ptr(region);
+            for (i, v) in bytes.chunks_exact(n).enumerate() {
+                let pos = match region {
+                    Region::Bit => bit_byte_pos(start + i),
+                    _ => (start + i) * n,
+                };
+                write_le(unsafe { base.add(pos) }, v);
+            }
+            self.generations.bump(region);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline() -> ValueTable {
+        let vt = ValueTable::new();
+        vt.set_bit(0x0003);
+        vt.set_bit(0x0100);
+        vt.set_u8(0x0010, 0x12);
+        vt.set_u16(0x0020, 0x3456);
+        vt.set_u32(0xFFFF, 0x789A_BCDE);
+        vt.set_u64(0x0000, 0x0102_0304_0506_0708);
+        vt
+    }
+
+    #[test]
+    fn combined_patch_round_trip() {
+        let since = baseline();
+        let cur = baseline();
+        cur.clear_bit(0x0003);
+        cur.set_bit(0x0004);
+        cur.set_bit(0x0200);
+        cur.set_u8(0x0010, 0x00);
+        cur.set_u16(0xFFFF, 0xBEEF);
+        cur.set_u32(0xFFFF, 0x1111_2222);
+        cur.set_i64(0x1234, -1);
+
+        let patch = cur.to_combined_patch(&since);
+        assert_eq!(u32::from_le_bytes(patch[4..8].try_into().unwrap()), 4);
+
+        let copy = baseline();
+        copy.apply_combined_patch(&patch).unwrap();
+        for region in [Region::Bit].into_iter().chain(Region::VALUES) {
+            assert!(copy.region_bytes(region) == cur.region_bytes(region));
+        }
+    }
+
+    #[test]
+    fn combined_patch_bit_layout() {
+        let since = ValueTable::new();
+        let cur = ValueTable::new();
+        cur.set_bit(0x0009);
+        cur.set_bit(0x0047);
+        let patch = cur.to_combined_patch(&since);
+        let mut expected = vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0];
+        expected.extend_from_slice(&[0x00, 0x02, 0, 0, 0, 0, 0, 0]);
+        expected.extend_from_slice(&[0x80, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(patch, expected);
+
+        since.apply_combined_patch(&patch).unwrap();
+        assert_eq!(since.iter_set_bits().collect::<Vec<_>>(), [0x0009, 0x0047]);
+    }
+
+    #[test]
+    fn combined_patch_unchanged() {
+        let a = baseline();
+        let b = baseline();
+        assert_eq!(a.to_combined_patch(&b), vec![0u8; HEADER_LEN]);
+    }
+
+    #[test]
+    fn combined_patch_malformed() {
+        let vt = baseline();
+        assert_eq!(
+            vt.apply_combined_patch(&[0, 0, 0]),
+            Err(XvtError::MalformedPatch)
+        );
+        let mut patch = vec![0, 0, 0, 0, 1, 0, 0, 0];
+        patch.extend_from_slice(&[Region::Bits16 as u8, 0x20, 0x00, 0xFF]);
+        assert_eq!(
+            vt.apply_combined_patch(&patch),
+            Err(XvtError::MalformedPatch)
+        );
+        assert_eq!(vt.get_u16(0x0020), 0x3456);
+    }
+
+    #[test]
+    fn combined_patch_out_of_capacity() {
+        let small = ValueTable::with_regions(crate::RegionConfig {
+            bits16: 4,
+            ..crate::RegionConfig::empty()
+        });
+        for (region, len) in [
+            (Region::Bits8, 1),
+            (Region::Bits16, 2),
+            (Region::Bits128, 16),
+        ] {
+            let mut patch = vec![0, 0, 0, 0, 1, 0, 0, 0];
+            patch.extend_from_slice(&[region as u8, 0xFF, 0xFF]);
+            patch.extend(core::iter::repeat_n(0xAB, len));
+            assert_eq!(
+                small.apply_combined_patch(&patch),
+                Err(XvtError::MalformedPatch)
+            );
+        }
+        let mut patch = vec![0, 0, 0, 0, 1, 0, 0, 0];
+        patch.extend_from_slice(&[Region::Bits16 as u8, 0x03, 0x00, 0xEF, 0xBE]);
+        small.apply_combined_patch(&patch).unwrap();
+        assert_eq!(small.get_u16(0x0003), 0xBEEF);
+    }
+
+    #[test]
+    fn patch_round_trip() {
+        let vt = baseline();
+        let since = vt.snapshot();
+        vt.set_bit(0x0000);
+        vt.set_bit(0x0009);
+        vt.clear_bit(0x0100);
+        vt.set_u8(0x0011, 0x34);
+        vt.set_u16s(0x0100, &[1, 2, 3]);
+        vt.set_u16(0x0200, 4);
+        vt.set_u32(0xFFFF, 0);
+        vt.set_f64(0x0040, 1.5);
+        vt.set_u128(0x0001, u128::MAX);
+
+        let patch = vt.encode_patch(&since);
+        let copy = ValueTable::from_bytes(&since).unwrap();
+        copy.apply_patch(&patch).unwrap();
+        assert!(copy.as_bytes() == vt.as_bytes());
+    }
+
+    #[test]
+    fn patch_coalesces_runs() {
+        let vt = ValueTable::new();
+        let since = vt.snapshot();
+        vt.set_u16s(0x0010, &[1, 2, 3]);
+        assert_eq!(
+            vt.encode_patch(&since),
+            [
+                Region::Bits16 as u8,
+                0x10,
+                0x00,
+                0x03,
+                0x00,
+                1,
+                0,
+                2,
+                0,
+                3,
+                0
+            ]
+        );
+        assert!(vt.encode_patch(&vt.snapshot()).is_empty());
+    }
+
+    #[test]
+    fn patch_malformed() {
+        let vt = baseline();
+        let overflow = [Region::Bits16 as u8, 0xFF, 0xFF, 0x02, 0x00, 0, 0, 0, 0];
+        assert_eq!(vt.apply_patch(&overflow), Err(XvtError::MalformedPatch));
+        let short = [Region::Bits16 as u8, 0x20, 0x00, 0x01, 0x00, 0xFF];
+        assert_eq!(vt.apply_patch(&short), Err(XvtError::MalformedPatch));
+        assert_eq!(vt.get_u16(0x0020), 0x3456);
+    }
+}
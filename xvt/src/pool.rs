@@ -0,0 +1,175 @@
+use crate::observe::Observers;
+use crate::region::RegionLayout;
+use crate::{RegionConfig, ValueTable};
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::AtomicU64;
+use std::sync::Mutex;
+
+/// 一个缓存存储表缓冲区以供反复使用的池。
+///
+/// 通过 [`ValueTablePool::acquire`] 取得的存储表在释放时清零并归还到池中，而不是释放内存，
+/// 从而把分配缓冲区的开销分摊到多次使用上。池中最多保留 `max_idle` 个空闲存储表，
+/// 超出的部分照常释放。池本身可以在线程间共享，也可以放在 `thread_local!` 中按线程使用。
+///
+/// # Examples
+///
+/// ```
+/// use xvt::ValueTablePool;
+///
+/// let pool = ValueTablePool::new(4);
+/// {
+///     let vt = pool.acquire();
+///     vt.set_u16(0x0001, 7);
+/// }
+/// assert_eq!(pool.acquire().get_u16(0x0001), 0);
+/// ```
+pub struct ValueTablePool {
+    config: RegionConfig,
+    max_idle: usize,
+    /// 空闲的存储表及其是否已清零。
+    idle: Mutex<Vec<(ValueTable, bool)>>,
+}
+
+impl ValueTablePool {
+    /// 构建一个按 [`RegionConfig::full`] 分配存储表、最多保留 `max_idle` 个空闲存储表的池。
+    pub fn new(max_idle: usize) -> Self {
+        Self::with_regions(RegionConfig::full(), max_idle)
+    }
+
+    /// 构建一个按区域配置 `config` 分配存储表、最多保留 `max_idle` 个空闲存储表的池。
+    pub fn with_regions(config: RegionConfig, max_idle: usize) -> Self {
+        Self {
+            config,
+            max_idle,
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 取得一个全零的存储表，池中没有空闲存储表时分配新的存储表。
+    pub fn acquire(&self) -> PooledValueTable<'_> {
+        let idle = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let vt = match idle {
+            Some((mut vt, zeroed)) => {
+                if !zeroed {
+                    vt.clear_all_mut();
+                }
+                vt
+            }
+            None => ValueTable::with_regions(self.config),
+        };
+        PooledValueTable {
+            pool: self,
+            vt: Some(vt),
+        }
+    }
+
+    /// 池中当前空闲的存储表个数。
+    pub fn idle(&self) -> usize {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    fn release(&self, mut vt: ValueTable, zeroed: bool) {
+        // 经 `swap_buffers` 换入的其他缓冲区不属于本池。
+        if vt.layout != RegionLayout::new(self.config) || !vt.backing.is_owned() {
+            return;
+        }
+        // 回调、脏标记等状态属于上一个使用者，不随缓冲区一起复用。
+        vt.observers = Observers::default();
+        vt.dirty = None;
+        vt.presence = None;
+        vt.version = AtomicU64::new(0);
+        vt.generations = Default::default();
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        if idle.len() < self.max_idle {
+            idle.push((vt, zeroed));
+        }
+    }
+}
+
+/// 由 [`ValueTablePool::acquire`] 返回的存储表，释放时归还到池中。
+pub struct PooledValueTable<'a> {
+    pool: &'a ValueTablePool,
+    vt: Option<ValueTable>,
+}
+
+impl PooledValueTable<'_> {
+    /// 不清零直接归还存储表，清零推迟到下一次 [`ValueTablePool::acquire`] 取得它时进行。
+    ///
+    /// 适合释放发生在对延迟敏感的路径上的场合。
+    pub fn release_unzeroed(mut self) {
+        if let Some(vt) = self.vt.take() {
+            self.pool.release(vt, false);
+        }
+    }
+}
+
+impl Deref for PooledValueTable<'_> {
+    type Target = ValueTable;
+
+    fn deref(&self) -> &ValueTable {
+        self.vt.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledValueTable<'_> {
+    fn deref_mut(&mut self) -> &mut ValueTable {
+        self.vt.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledValueTable<'_> {
+    fn drop(&mut self) {
+        if let Some(mut vt) = self.vt.take() {
+            vt.clear_all_mut();
+            self.pool.release(vt, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Region;
+
+    #[test]
+    fn reuses_zeroed_buffer() {
+        let pool = ValueTablePool::new(1);
+        let vt = pool.acquire();
+        let (ptr, _) = unsafe { vt.as_raw_parts() };
+        vt.set_u64(0x0010, u64::MAX);
+        vt.set_bit(0x0001);
+        drop(vt);
+        assert_eq!(pool.idle(), 1);
+
+        let vt = pool.acquire();
+        assert_eq!(unsafe { vt.as_raw_parts() }.0, ptr);
+        assert!(vt.as_bytes().iter().all(|b| *b == 0));
+        assert_eq!(pool.idle(), 0);
+
+        let other = pool.acquire();
+        drop(vt);
+        drop(other);
+        assert_eq!(pool.idle(), 1);
+    }
+
+    #[test]
+    fn release_unzeroed() {
+        let pool = ValueTablePool::with_regions(
+            RegionConfig {
+                bits16: 4,
+                ..RegionConfig::empty()
+            },
+            2,
+        );
+        let mut vt = pool.acquire();
+        vt.region_mut_u16()[3] = 9;
+        assert_eq!(vt.region_generation(Region::Bits16), 0);
+        vt.set_u16(0x0000, 1);
+        vt.release_unzeroed();
+
+        let vt = pool.acquire();
+        assert_eq!(vt.get_u16s(0x0000, 4), &[0, 0, 0, 0]);
+        assert_eq!(vt.region_generation(Region::Bits16), 0);
+        assert_eq!(vt.region_capacity(Region::Bits16), 4);
+    }
+}
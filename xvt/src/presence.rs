@@ -0,0 +1,94 @@
+use crate::dirty::DirtyMap;
+use crate::{Region, ValueTable};
+use paste::paste;
+
+macro_rules! impl_presence {
+    ($t:ty, $n:expr) => {
+        paste! {
+            #[doc = "获取指定地址 `addr` 类型为 `" $t "` 的值，超出区域容量或从未写入时返回 `None`。"]
+            ///
+            /// 存储表未开启写入跟踪时，与对应的 `get_*_checked` 相同。
+            pub fn [<get_ $t _opt>](&self, addr: u16) -> Option<$t> {
+                if !self.is_written(Region::[<Bits $n>], addr) {
+                    return None;
+                }
+                self.[<get_ $t _checked>](addr)
+            }
+        }
+    };
+}
+
+impl ValueTable {
+    /// 构建一个跟踪已写入地址的多种值存储表实例，用于区分“值为零”与“从未写入”。
+    ///
+    /// 通过 `set_*` 系列、`set_bit`、`clear_bit` 或原子操作写入过的地址会被记录，无论写入的值是什么，
+    /// 之后可通过 `get_*_opt` 读取；批量操作（如 [`ValueTable::fill_u16`]）与经
+    /// [`ValueTable::as_atomic_u32`] 的写入不会记录，[`ValueTable::clear_all`] 也不会清除已有的记录。
+    pub fn new_presence_tracked() -> Self {
+        let mut vt = Self::new();
+        vt.presence = Some(DirtyMap::new());
+        vt
+    }
+
+    /// 区域 `region` 中的地址 `addr` 是否被写入过。
+    ///
+    /// 存储表未开启写入跟踪时总是返回 `true`。
+    pub fn is_written(&self, region: Region, addr: u16) -> bool {
+        match &self.presence {
+            Some(presence) => presence.is_marked(region, addr),
+            None => true,
+        }
+    }
+
+    /// 获取指定地址 `addr` 的单比特值，超出区域容量或从未写入时返回 `None`。
+    pub fn get_bit_opt(&self, addr: u16) -> Option<bool> {
+        if addr as usize >= self.region_capacity(Region::Bit) || !self.is_written(Region::Bit, addr)
+        {
+            return None;
+        }
+        Some(self.get_bit(addr))
+    }
+
+    impl_presence!(i8, 8);
+    impl_presence!(i16, 16);
+    impl_presence!(i32, 32);
+    impl_presence!(i64, 64);
+    impl_presence!(u8, 8);
+    impl_presence!(u16, 16);
+    impl_presence!(u32, 32);
+    impl_presence!(u64, 64);
+    impl_presence!(f32, 32);
+    impl_presence!(f64, 64);
+    impl_presence!(i128, 128);
+    impl_presence!(u128, 128);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_opt() {
+        let vt = ValueTable::new_presence_tracked();
+        assert_eq!(vt.get_u16_opt(0x0010), None);
+        vt.set_u16(0x0010, 0);
+        assert_eq!(vt.get_u16_opt(0x0010), Some(0));
+        vt.set_u16(0x0010, 0xBEEF);
+        assert_eq!(vt.get_u16_opt(0x0010), Some(0xBEEF));
+        assert_eq!(vt.get_u16_opt(0x0011), None);
+        assert_eq!(vt.get_i16_opt(0x0010), Some(0xBEEFu16 as i16));
+
+        assert_eq!(vt.get_bit_opt(0x0001), None);
+        vt.clear_bit(0x0001);
+        assert_eq!(vt.get_bit_opt(0x0001), Some(false));
+        vt.set_f64s(0xFFFE, &[1.0, 2.0]);
+        assert_eq!(vt.get_f64_opt(0xFFFF), Some(2.0));
+
+        let copy = vt.clone();
+        assert_eq!(copy.get_u16_opt(0x0010), Some(0xBEEF));
+        assert_eq!(copy.get_u16_opt(0x0011), None);
+
+        let plain = ValueTable::new();
+        assert_eq!(plain.get_u16_opt(0x0011), Some(0));
+    }
+}
@@ -0,0 +1,569 @@
+use crate::{LayoutError, ValueTable};
+
+/// 一个代表值存储表中数据区域的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Region {
+    /// 单比特值区域。
+    Bit = 0,
+    /// 8 位整数值区域。
+    Bits8 = 1,
+    /// 16 位整数值区域。
+    Bits16 = 2,
+    /// 32 位整数、浮点值区域。
+    Bits32 = 3,
+    /// 64 位整数、浮点值区域。
+    Bits64 = 4,
+    /// 128 位整数值区域。
+    Bits128 = 5,
+}
+
+/// 一个代表存储表中各区域容量配置的类型。
+///
+/// 每个字段为对应区域可容纳的值个数，超过 `65536` 的部分将被忽略，为 `0` 时不分配该区域；
+/// 单比特区域的容量向上取整为 64 的整数倍。
+///
+/// # Examples
+///
+/// ```
+/// use xvt::{RegionConfig, ValueTable};
+///
+/// let vt = ValueTable::with_regions(RegionConfig {
+///     bits: 1024,
+///     bits8: 256,
+///     ..RegionConfig::empty()
+/// });
+/// vt.set_u8(0x00FF, 1);
+/// vt.set_u16(0x0000, 1);
+/// assert_eq!(vt.get_u8(0x00FF), 1);
+/// assert_eq!(vt.get_u16(0x0000), 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegionConfig {
+    /// 单比特值的个数。
+    pub bits: usize,
+    /// 8 位值的个数。
+    pub bits8: usize,
+    /// 16 位值的个数。
+    pub bits16: usize,
+    /// 32 位值的个数。
+    pub bits32: usize,
+    /// 64 位值的个数。
+    pub bits64: usize,
+    /// 128 位值的个数。
+    pub bits128: usize,
+}
+
+impl RegionConfig {
+    /// 所有区域都占用完整 `u16` 地址空间的配置，即 [`ValueTable::new`] 使用的布局。
+    pub const fn full() -> Self {
+        Self {
+            bits: ValueTable::BIT_REG_CAP,
+            bits8: ValueTable::BITS_8_REG_CAP,
+            bits16: ValueTable::BITS_16_REG_CAP,
+            bits32: ValueTable::BITS_32_REG_CAP,
+            bits64: ValueTable::BITS_64_REG_CAP,
+            bits128: ValueTable::BITS_128_REG_CAP,
+        }
+    }
+
+    /// 是否与 [`RegionConfig::full`] 相同，可在常量上下文中使用。
+    pub(crate) const fn is_full(&self) -> bool {
+        let full = Self::full();
+        self.bits == full.bits
+            && self.bits8 == full.bits8
+            && self.bits16 == full.bits16
+            && self.bits32 == full.bits32
+            && self.bits64 == full.bits64
+            && self.bits128 == full.bits128
+    }
+
+    /// 不分配任何区域的配置。
+    pub const fn empty() -> Self {
+        Self {
+            bits: 0,
+            bits8: 0,
+            bits16: 0,
+            bits32: 0,
+            bits64: 0,
+            bits128: 0,
+        }
+    }
+}
+
+impl Default for RegionConfig {
+    fn default() -> Self {
+        Self::full()
+    }
+}
+
+/// 按区域配置计算出的存储表布局。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RegionLayout {
+    /// 各区域的字节偏移。
+    ofs: [usize; Region::COUNT],
+    /// 各区域可容纳的值个数。
+    cap: [usize; Region::COUNT],
+    /// 存储表占用的字节数。
+    size: usize,
+    /// 每个区域之后的哨兵字节数，为 `0` 时不放置哨兵。
+    guard: usize,
+}
+
+impl RegionLayout {
+    /// 缓冲区基址的对齐值，不小于任何区域值类型的对齐要求。
+    pub(crate) const ALIGN: usize = 4096;
+
+    /// 依次排列各区域，每个区域的偏移按 8 字节与其值宽度中的较大者对齐。
+    ///
+    /// 基本类型的宽度总是其对齐值的整数倍，因此每个区域的起始地址都满足对应类型的对齐要求，
+    /// 带类型的访问器可以直接把区域指针转换为 `*mut T`。
+    pub(crate) const fn new(config: RegionConfig) -> Self {
+        Self::guarded(config, 0)
+    }
+
+    /// 与 [`RegionLayout::new`] 相同，但在每个区域之后留出 `guard` 字节放置哨兵。
+    pub(crate) const fn guarded(config: RegionConfig, guard: usize) -> Self {
+        const MAX: usize = 65536;
+        const fn clamp(n: usize) -> usize {
+            if n < MAX {
+                n
+            } else {
+                MAX
+            }
+        }
+        let cap = [
+            clamp(config.bits).next_multiple_of(64),
+            clamp(config.bits8),
+            clamp(config.bits16),
+            clamp(config.bits32),
+            clamp(config.bits64),
+            clamp(config.bits128),
+        ];
+        let mut ofs = [0; Region::COUNT];
+        let mut end = cap[0] / 8 + guard;
+        let mut i = 0;
+        while i < Region::VALUES.len() {
+            let region = Region::VALUES[i];
+            let n = region.element_size();
+            end = end.next_multiple_of(if n > 8 { n } else { 8 });
+            ofs[region as usize] = end;
+            end += cap[region as usize] * n + guard;
+            i += 1;
+        }
+        // 完整布局向上取整为 2 MiB。
+        let size = if config.is_full() && end <= ValueTable::BUFFER_SIZE {
+            ValueTable::BUFFER_SIZE
+        } else if end > 8 {
+            end
+        } else {
+            8
+        };
+        Self {
+            ofs,
+            cap,
+            size,
+            guard,
+        }
+    }
+
+    /// 每个区域之后的哨兵字节数。
+    pub(crate) fn guard(&self) -> usize {
+        self.guard
+    }
+
+    /// 区域 `region` 的字节偏移。
+    pub(crate) fn offset(&self, region: Region) -> usize {
+        self.ofs[region as usize]
+    }
+
+    /// 区域 `region` 可容纳的值个数。
+    pub(crate) fn capacity(&self, region: Region) -> usize {
+        self.cap[region as usize]
+    }
+
+    /// 区域 `region` 占用的字节数。
+    pub(crate) fn byte_len(&self, region: Region) -> usize {
+        match region {
+            Region::Bit => self.cap[0] / 8,
+            _ => self.cap[region as usize] * region.element_size(),
+        }
+    }
+
+    /// 存储表占用的字节数。
+    pub(crate) const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// 分配存储表缓冲区使用的内存布局，分配与释放必须使用同一布局。
+    pub(crate) fn alloc_layout(&self) -> alloc::alloc::Layout {
+        unsafe { alloc::alloc::Layout::from_size_align_unchecked(self.size, Self::ALIGN) }
+    }
+}
+
+impl Region {
+    /// 区域的个数。
+    pub(crate) const COUNT: usize = 6;
+
+    /// 所有区域，按在存储中的先后顺序排列。
+    pub const ALL: &'static [Region] = &[
+        Region::Bit,
+        Region::Bits8,
+        Region::Bits16,
+        Region::Bits32,
+        Region::Bits64,
+        Region::Bits128,
+    ];
+
+    /// 所有带类型的数值区域。
+    pub(crate) const VALUES: [Region; 5] = [
+        Region::Bits8,
+        Region::Bits16,
+        Region::Bits32,
+        Region::Bits64,
+        Region::Bits128,
+    ];
+
+    /// 从编码标识还原区域。
+    pub(crate) fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Region::Bit),
+            1 => Some(Region::Bits8),
+            2 => Some(Region::Bits16),
+            3 => Some(Region::Bits32),
+            4 => Some(Region::Bits64),
+            5 => Some(Region::Bits128),
+            _ => None,
+        }
+    }
+
+    /// 区域在完整布局（[`RegionConfig::full`]）中的字节偏移。
+    pub fn byte_offset(self) -> usize {
+        match self {
+            Region::Bit => 0,
+            Region::Bits8 => ValueTable::BITS_8_REG_OFS,
+            Region::Bits16 => ValueTable::BITS_16_REG_OFS,
+            Region::Bits32 => ValueTable::BITS_32_REG_OFS,
+            Region::Bits64 => ValueTable::BITS_64_REG_OFS,
+            Region::Bits128 => ValueTable::BITS_128_REG_OFS,
+        }
+    }
+
+    /// 区域在完整布局中占用的字节数。
+    pub fn byte_len(self) -> usize {
+        match self {
+            Region::Bit => ValueTable::BIT_REG_CAP / 8,
+            Region::Bits8 => ValueTable::BITS_8_REG_CAP,
+            Region::Bits16 => ValueTable::BITS_16_REG_CAP * 2,
+            Region::Bits32 => ValueTable::BITS_32_REG_CAP * 4,
+            Region::Bits64 => ValueTable::BITS_64_REG_CAP * 8,
+            Region::Bits128 => ValueTable::BITS_128_REG_CAP * 16,
+        }
+    }
+
+    /// 区域中单个值占用的字节数，单比特区域为 `0`。
+    pub const fn element_size(self) -> usize {
+        match self {
+            Region::Bit => 0,
+            Region::Bits8 => 1,
+            Region::Bits16 => 2,
+            Region::Bits32 => 4,
+            Region::Bits64 => 8,
+            Region::Bits128 => 16,
+        }
+    }
+
+    /// 区域在完整布局中可容纳的值个数，每个区域都独立占用完整的 `u16` 地址空间。
+    pub fn capacity(self) -> usize {
+        match self {
+            Region::Bit => ValueTable::BIT_REG_CAP,
+            Region::Bits8 => ValueTable::BITS_8_REG_CAP,
+            Region::Bits16 => ValueTable::BITS_16_REG_CAP,
+            Region::Bits32 => ValueTable::BITS_32_REG_CAP,
+            Region::Bits64 => ValueTable::BITS_64_REG_CAP,
+            Region::Bits128 => ValueTable::BITS_128_REG_CAP,
+        }
+    }
+
+    /// 区域在完整布局中的最小及最大地址（含），每个区域都是 `(0, 65535)`。
+    pub fn bounds(self) -> (u16, u16) {
+        (0, (self.capacity() - 1) as u16)
+    }
+
+    /// 区域中地址 `addr` 的值在完整布局中的字节偏移。
+    ///
+    /// 单比特区域按机器字存储，返回的是包含该位的 `usize` 字的偏移。
+    pub fn addr_offset(self, addr: u16) -> usize {
+        match self {
+            Region::Bit => ValueTable::bit_location(addr).0 * core::mem::size_of::<usize>(),
+            _ => self.byte_offset() + addr as usize * self.element_size(),
+        }
+    }
+}
+
+impl ValueTable {
+    /// 将完整布局中的字节偏移 `offset` 归类到所属的区域及地址。
+    ///
+    /// 与 `Registers` 不同，存储表中每个区域都独立使用完整的 `u16` 地址空间，
+    /// 因此仅凭地址无法确定区域，只能由字节偏移反查。对于单比特区域，返回
+    /// 包含该字节的机器字中的第一个位地址；偏移超出区域布局时返回 `None`。
+    pub fn classify(offset: usize) -> Option<(Region, u16)> {
+        const WORD: usize = core::mem::size_of::<usize>();
+        if offset < Self::BITS_8_REG_OFS {
+            return Some((Region::Bit, (offset / WORD * WORD * 8) as u16));
+        }
+        Region::VALUES
+            .into_iter()
+            .find(|r| offset < r.byte_offset() + r.byte_len())
+            .map(|r| (r, ((offset - r.byte_offset()) / r.element_size()) as u16))
+    }
+
+    /// 在运行时校验完整布局：各区域的字节范围互不重叠、都位于缓冲区之内、起始偏移满足
+    /// 对应值类型的对齐要求，且与 [`RegionConfig::full`] 计算出的布局一致。
+    ///
+    /// 这些条件大多已由编译期断言保证，本函数便于在启动自检或测试中显式确认。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// assert_eq!(xvt::ValueTable::validate_layout(), Ok(()));
+    /// ```
+    pub fn validate_layout() -> Result<(), LayoutError> {
+        let layout = RegionLayout::new(RegionConfig::full());
+        let mut spans = [(Region::Bit, 0, 0); Region::COUNT];
+        for (span, &region) in spans.iter_mut().zip(Region::ALL) {
+            if region.byte_offset() != layout.offset(region)
+                || region.byte_len() != layout.byte_len(region)
+            {
+                return Err(LayoutError::Mismatch(region));
+            }
+            *span = (region, region.byte_offset(), region.byte_len());
+        }
+        check_spans(&spans, Self::BUFFER_SIZE)
+    }
+}
+
+/// 校验以 `(区域, 字节偏移, 字节数)` 给出的各区域范围能否放入 `size` 字节的缓冲区。
+fn check_spans(spans: &[(Region, usize, usize)], size: usize) -> Result<(), LayoutError> {
+    for (i, &(region, ofs, len)) in spans.iter().enumerate() {
+        let align = match region {
+            Region::Bit => core::mem::align_of::<usize>(),
+            _ => region.element_size(),
+        };
+        if !ofs.is_multiple_of(align) {
+            return Err(LayoutError::Misaligned(region));
+        }
+        if ofs.checked_add(len).is_none_or(|end| end > size) {
+            return Err(LayoutError::OutOfBounds(region));
+        }
+        for &(other, o_ofs, o_len) in &spans[..i] {
+            if ofs < o_ofs + o_len && o_ofs < ofs + len {
+                return Err(LayoutError::Overlap(other, region));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn addr_offset() {
+        let vt = ValueTable::new();
+        vt.set_u8(0x0102, 0xA5);
+        vt.set_u16(0x0304, 0xBEEF);
+        vt.set_u32(0xFFFF, 0x1234_5678);
+        vt.set_u64(0x0506, u64::MAX - 1);
+        vt.set_bit(0x0123);
+        let bytes = vt.as_bytes();
+        let at = |r: Region, addr: u16| &bytes[r.addr_offset(addr)..][..r.element_size()];
+        assert_eq!(at(Region::Bits8, 0x0102), &[0xA5]);
+        assert_eq!(at(Region::Bits16, 0x0304), &0xBEEFu16.to_ne_bytes());
+        assert_eq!(at(Region::Bits32, 0xFFFF), &0x1234_5678u32.to_ne_bytes());
+        assert_eq!(at(Region::Bits64, 0x0506), &(u64::MAX - 1).to_ne_bytes());
+        let ofs = Region::Bit.addr_offset(0x0123);
+        let word = usize::from_ne_bytes(
+            bytes[ofs..][..core::mem::size_of::<usize>()]
+                .try_into()
+                .unwrap(),
+        );
+        assert_eq!(word, ValueTable::bit_location(0x0123).1);
+    }
+
+    #[test]
+    fn full_layout() {
+        let layout = RegionLayout::new(RegionConfig::full());
+        for r in [Region::Bit].into_iter().chain(Region::VALUES) {
+            assert_eq!(layout.offset(r), r.byte_offset());
+            assert_eq!(layout.byte_len(r), r.byte_len());
+            assert_eq!(layout.capacity(r), r.capacity());
+        }
+        assert_eq!(layout.size(), ValueTable::BUFFER_SIZE);
+    }
+
+    #[test]
+    fn all_regions() {
+        let mut end = 0;
+        for region in Region::ALL {
+            assert_eq!(region.bounds(), (0, 0xFFFF));
+            assert!(region.byte_offset() >= end);
+            end = region.byte_offset() + region.byte_len();
+        }
+        assert!(end <= ValueTable::BUFFER_SIZE);
+        assert_eq!(Region::ALL[1..], Region::VALUES);
+    }
+
+    #[test]
+    fn aligned_layouts() {
+        use core::mem::align_of;
+        let aligns = [
+            (Region::Bit, align_of::<usize>()),
+            (Region::Bits16, align_of::<u16>()),
+            (Region::Bits32, align_of::<u32>().max(align_of::<f32>())),
+            (Region::Bits64, align_of::<u64>().max(align_of::<f64>())),
+            (Region::Bits128, align_of::<u128>()),
+        ];
+        let configs = [
+            RegionConfig::full(),
+            RegionConfig::empty(),
+            RegionConfig {
+                bits: 1,
+                bits8: 3,
+                bits16: 1,
+                bits32: 1,
+                bits64: 1,
+                bits128: 1,
+            },
+            RegionConfig {
+                bits8: 7,
+                bits128: 2,
+                ..RegionConfig::empty()
+            },
+        ];
+        for config in configs {
+            let vt = ValueTable::with_regions(config);
+            for (region, align) in aligns {
+                assert_eq!(vt.region_ptr(region) as usize % align, 0, "{region:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn custom_layout() {
+        let vt = ValueTable::with_regions(RegionConfig {
+            bits: 100,
+            bits16: 3,
+            bits64: 2,
+            ..RegionConfig::empty()
+        });
+        assert_eq!(vt.region_capacity(Region::Bit), 128);
+        assert_eq!(vt.region_capacity(Region::Bits8), 0);
+        assert_eq!(vt.region_offset(Region::Bits16), 16);
+        assert_eq!(vt.region_offset(Region::Bits32), 24);
+        assert_eq!(vt.region_offset(Region::Bits64), 24);
+        assert_eq!(vt.region_offset(Region::Bits128), 48);
+        assert_eq!(vt.as_bytes().len(), 48);
+
+        vt.set_bit(127);
+        vt.set_bit(128);
+        assert!(vt.get_bit(127));
+        assert!(!vt.get_bit(128));
+        vt.set_u16s(0x0001, &[1, 2, 3, 4]);
+        assert_eq!(vt.get_u16s(0x0000, 8), &[0, 1, 2]);
+        vt.set_u8(0x0000, 1);
+        vt.set_u32(0x0000, 1);
+        assert_eq!((vt.get_u8(0x0000), vt.get_u32(0x0000)), (0, 0));
+        assert!(vt.get_u32s(0x0000, 4).is_empty());
+        vt.set_f64(0x0001, 1.5);
+        assert_eq!(vt.get_f64(0x0001), 1.5);
+        assert_eq!(vt.get_f64(0x0002), 0.0);
+
+        let copy = vt.clone();
+        assert_eq!(copy.get_u16s(0x0000, 3), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn bit_only() {
+        let vt = ValueTable::with_regions(RegionConfig {
+            bits: 65536,
+            ..RegionConfig::empty()
+        });
+        assert_eq!(vt.as_bytes().len(), 8192);
+        vt.set_bit(0xFFFF);
+        assert!(vt.get_bit(0xFFFF));
+        for addr in [0x0000, 0xFFFF] {
+            vt.set_u8(addr, 0xFF);
+            vt.set_u16(addr, 0xFFFF);
+            vt.set_u64(addr, u64::MAX);
+            assert_eq!(vt.get_u8(addr), 0);
+            assert_eq!(vt.get_u16(addr), 0);
+            assert_eq!(vt.get_u64(addr), 0);
+        }
+        assert_eq!(vt.set_u32s(0x0000, &[1, 2]), 0);
+        assert_eq!(vt.iter_set_bits().collect::<Vec<_>>(), [0xFFFF]);
+    }
+
+    #[test]
+    fn no_bits() {
+        let configs = [
+            RegionConfig::empty(),
+            RegionConfig {
+                bits: 0,
+                bits16: 4,
+                ..RegionConfig::empty()
+            },
+        ];
+        for config in configs {
+            let vt = ValueTable::with_regions(config);
+            assert_eq!(vt.region_capacity(Region::Bit), 0);
+            vt.set_bit(0x0000);
+            assert!(!vt.get_bit(0x0000));
+            assert_eq!(vt.iter_set_bits().count(), 0);
+            assert_eq!(vt.count_set_bits(0, 0xFFFF), 0);
+        }
+    }
+
+    #[test]
+    fn classify() {
+        for r in Region::VALUES {
+            for addr in [0x0000, 0x1234, 0xFFFF] {
+                let ofs = r.addr_offset(addr);
+                assert_eq!(ValueTable::classify(ofs), Some((r, addr)));
+                assert_eq!(
+                    ValueTable::classify(ofs + r.element_size() - 1),
+                    Some((r, addr))
+                );
+            }
+            assert_eq!(r.capacity() * r.element_size(), r.byte_len());
+        }
+        let ofs = Region::Bit.addr_offset(0x0123);
+        let (r, addr) = ValueTable::classify(ofs).unwrap();
+        assert_eq!(r, Region::Bit);
+        assert_eq!(Region::Bit.addr_offset(addr), ofs);
+        assert_eq!(ValueTable::classify(ValueTable::BITS_128_REG_END), None);
+    }
+
+    #[test]
+    fn validate_layout() {
+        assert_eq!(ValueTable::validate_layout(), Ok(()));
+
+        let good = [(Region::Bit, 0, 8), (Region::Bits16, 8, 8)];
+        assert_eq!(check_spans(&good, 16), Ok(()));
+        assert_eq!(
+            check_spans(&good, 15),
+            Err(LayoutError::OutOfBounds(Region::Bits16))
+        );
+        let overlap = [(Region::Bit, 0, 8), (Region::Bits16, 6, 8)];
+        assert_eq!(
+            check_spans(&overlap, 16),
+            Err(LayoutError::Overlap(Region::Bit, Region::Bits16))
+        );
+        let misaligned = [(Region::Bit, 0, 8), (Region::Bits32, 10, 8)];
+        assert_eq!(
+            check_spans(&misaligned, 32),
+            Err(LayoutError::Misaligned(Region::Bits32))
+        );
+    }
+}
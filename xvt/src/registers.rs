@@ -1,6 +1,32 @@
+use crate::Region;
+use alloc::alloc::{alloc_zeroed, dealloc, Layout};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::fmt;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicU16, AtomicU8};
 use paste::paste;
-use std::alloc::{alloc_zeroed, dealloc, Layout};
-use std::ptr::{self, NonNull};
+
+pub(crate) mod modbus;
+
+/// 一个代表寄存器访问错误的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegError {
+    /// 访问范围超出了区域的末尾。
+    OutOfRange,
+    /// 寄存器地址不属于所访问类型的区域。
+    RegionMismatch,
+}
+
+impl fmt::Display for RegError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegError::OutOfRange => write!(f, "访问范围超出区域末尾"),
+            RegError::RegionMismatch => write!(f, "寄存器地址不属于所访问的区域"),
+        }
+    }
+}
+
+impl core::error::Error for RegError {}
 
 /// 一个代表寄存器的类型。
 ///
@@ -11,53 +37,88 @@ use std::ptr::{self, NonNull};
 /// * `0x8000 ~ 0xBFFF` - 16 位整数值。
 /// * `0xC000 ~ 0xDFFF` - 32 位整数、浮点值。
 /// * `0xE000 ~ 0xFFFF` - 64 位整数、浮点值。
-struct ModBusRegisters {
+///
+/// # Examples
+///
+/// ```
+/// use xvt::Registers;
+///
+/// let regs = Registers::new();
+/// regs.set_u16(Registers::BITS_16_REG_MIN, 0x1234);
+/// assert_eq!(regs.get_u16(Registers::BITS_16_REG_MIN), 0x1234);
+/// ```
+pub struct Registers {
     mem: NonNull<u8>,
+    /// 对 Modbus 客户端只读的区域，按 [`Region`] 的编号逐位标记。
+    read_only: AtomicU8,
+    /// 与保持寄存器地址相同、但相互独立的输入寄存器。
+    inputs: Box<[AtomicU16]>,
 }
 
 macro_rules! impl_bits {
     ($t:ty, $n:expr) => {
         paste! {
+            #[doc = "获取指定寄存器 `reg` 类型为 `" $t "` 的值，`reg` 不属于该区域时返回错误。"]
+            pub fn [<try_get_ $t>](&self, reg: u16) -> Result<$t, RegError> {
+                let offset = Self::offset_in(reg, Self::[<BITS_ $n _REG_MIN>], Self::[<BITS_ $n _REG_MAX>])?
+                    + Self::[<BITS_ $n _REG_OFS>];
+                unsafe { Ok(*self.mem.cast::<$t>().as_ptr().add(offset as usize)) }
+            }
+
+            #[doc = "获取指定寄存器 `reg` 类型为 `" $t "` 的值，`reg` 不属于该区域时返回 `0`。"]
             pub fn [<get_ $t>](&self, reg: u16) -> $t {
-                unsafe {
-                    if reg < Self::[<BITS_ $n _REG_MIN>] || reg > Self::[<BITS_ $n _REG_MAX>] {
-                        return 0;
-                    }
-                    let offset = reg - Self::[<BITS_ $n _REG_MIN>] + Self::[<BITS_ $n _REG_OFS>];
-                    let val_ptr = self.mem.cast::<$t>().as_ptr().offset(offset as isize);
-                    *val_ptr
-                }
+                self.[<try_get_ $t>](reg).unwrap_or(0)
             }
 
+            #[doc = "获取从寄存器 `reg` 开始类型为 `" $t "` 的 `num` 个值，范围不完全位于该区域时返回空切片。"]
             pub fn [<get_ $t _values>](&self, reg: u16, num: u16) -> &[$t] {
-                unsafe {
-                    if reg < Self::[<BITS_ $n _REG_MIN>] || reg > Self::[<BITS_ $n _REG_MAX>] || (reg + num) > Self::[<BITS_ $n _REG_MAX>] {
-                        return &[];
-                    }
-                    let offset = reg - Self::[<BITS_ $n _REG_MIN>] + Self::[<BITS_ $n _REG_OFS>];
-                    let val_ptr = self.mem.cast::<$t>().as_ptr().offset(offset as isize);
-                    std::slice::from_raw_parts(val_ptr, num as usize)
+                match Self::span_in(reg, num as usize, Self::[<BITS_ $n _REG_MIN>], Self::[<BITS_ $n _REG_MAX>]) {
+                    Ok(offset) => unsafe {
+                        let val_ptr = self.mem.cast::<$t>().as_ptr().add((offset + Self::[<BITS_ $n _REG_OFS>]) as usize);
+                        core::slice::from_raw_parts(val_ptr, num as usize)
+                    },
+                    Err(_) => &[],
                 }
             }
 
-            pub fn [<set_ $t>](&self, reg: u16, val: $t) {
+            #[doc = "获取从寄存器 `reg` 开始类型为 `" $t "` 的至多 `num` 个值，`reg` 不属于该区域时返回错误。"]
+            ///
+            /// 范围越过区域末尾时截断到区域末尾，调用方可比较返回切片的长度与 `num` 判断是否发生了截断。
+            pub fn [<get_ $t _values_checked>](&self, reg: u16, num: u16) -> Result<&[$t], RegError> {
+                let offset = Self::offset_in(reg, Self::[<BITS_ $n _REG_MIN>], Self::[<BITS_ $n _REG_MAX>])?;
+                let n = (num as usize).min((Self::[<BITS_ $n _REG_MAX>] - reg) as usize + 1);
                 unsafe {
-                    if reg >= Self::[<BITS_ $n _REG_MIN>] && reg <= Self::[<BITS_ $n _REG_MAX>] {
-                        let offset = reg - Self::[<BITS_ $n _REG_MIN>] + Self::[<BITS_ $n _REG_OFS>];
-                        let val_ptr = self.mem.cast::<$t>().as_ptr().offset(offset as isize);
-                        *val_ptr = val;
-                    }
+                    let val_ptr = self.mem.cast::<$t>().as_ptr().add((offset + Self::[<BITS_ $n _REG_OFS>]) as usize);
+                    Ok(core::slice::from_raw_parts(val_ptr, n))
                 }
             }
 
-            pub fn [<set_ $t _values>](&self, reg: u16, values: &[$t]) {
+            #[doc = "设置指定寄存器 `reg` 类型为 `" $t "` 的值，`reg` 不属于该区域时返回错误。"]
+            pub fn [<try_set_ $t>](&self, reg: u16, val: $t) -> Result<(), RegError> {
+                let offset = Self::offset_in(reg, Self::[<BITS_ $n _REG_MIN>], Self::[<BITS_ $n _REG_MAX>])?
+                    + Self::[<BITS_ $n _REG_OFS>];
+                unsafe { *self.mem.cast::<$t>().as_ptr().add(offset as usize) = val };
+                Ok(())
+            }
+
+            #[doc = "设置指定寄存器 `reg` 类型为 `" $t "` 的值，`reg` 不属于该区域时不做任何操作。"]
+            pub fn [<set_ $t>](&self, reg: u16, val: $t) {
+                let _ = self.[<try_set_ $t>](reg, val);
+            }
+
+            #[doc = "设置从寄存器 `reg` 开始类型为 `" $t "` 的多个值，返回实际写入的值个数。"]
+            ///
+            /// 范围越过区域末尾时截断到区域末尾；`reg` 不属于该区域时不做任何操作并返回 `0`。
+            pub fn [<set_ $t _values>](&self, reg: u16, values: &[$t]) -> usize {
+                let Ok(offset) = Self::offset_in(reg, Self::[<BITS_ $n _REG_MIN>], Self::[<BITS_ $n _REG_MAX>]) else {
+                    return 0;
+                };
+                let n = values.len().min((Self::[<BITS_ $n _REG_MAX>] - reg) as usize + 1);
                 unsafe {
-                    if reg >= Self::[<BITS_ $n _REG_MIN>] && reg <= Self::[<BITS_ $n _REG_MAX>] && (reg + values.len() as u16) <= Self::[<BITS_ $n _REG_MAX>] {
-                        let offset = reg - Self::[<BITS_ $n _REG_MIN>] + Self::[<BITS_ $n _REG_OFS>];
-                        let val_ptr = self.mem.cast::<$t>().as_ptr().offset(offset as isize);
-                        std::ptr::copy(values.as_ptr(), val_ptr, values.len());
-                    }
+                    let val_ptr = self.mem.cast::<$t>().as_ptr().add((offset + Self::[<BITS_ $n _REG_OFS>]) as usize);
+                    core::ptr::copy(values.as_ptr(), val_ptr, n);
                 }
+                n
             }
         }
     }
@@ -89,51 +150,136 @@ impl Registers {
     const BITS_32_REG_OFS: u16 = Self::BITS_16_REG_OFS + Self::BITS_16_REG_NUM;
     const BITS_64_REG_OFS: u16 = Self::BITS_32_REG_OFS + Self::BITS_32_REG_NUM;
 
-    fn new() -> Self {
+    /// 存储表占用的字节数。
+    const BUFFER_SIZE: usize = 1024 * 1024;
+
+    /// 构建一个寄存器实例。
+    pub fn new() -> Self {
         unsafe {
-            let layout = Layout::from_size_align_unchecked(1024 * 1024 * 1024, 4096);
+            let layout = Layout::from_size_align_unchecked(Self::BUFFER_SIZE, 4096);
             let ptr = alloc_zeroed(layout);
             Self {
                 mem: NonNull::new_unchecked(ptr),
+                read_only: AtomicU8::new(0),
+                inputs: (0..Self::BITS_16_REG_NUM)
+                    .map(|_| AtomicU16::new(0))
+                    .collect(),
             }
         }
     }
 
-    pub fn get_bit(&self, reg: u16) -> bool {
-        unsafe {
-            const N: u16 = std::mem::size_of::<usize>() as u16;
-            if reg > Self::BIT_REG_MAX {
-                return false;
-            }
-            let offset = reg / N;
-            let val: usize = self
-                .mem
-                .cast::<usize>()
-                .as_ptr()
-                .offset(offset as isize)
-                .read();
-            val & (1 << (reg % N)) != 0
+    /// 检查寄存器 `reg` 是否位于区域 `[min, max]` 中，返回其在区域中的偏移。
+    fn offset_in(reg: u16, min: u16, max: u16) -> Result<u16, RegError> {
+        if (min..=max).contains(&reg) {
+            Ok(reg - min)
+        } else {
+            Err(RegError::RegionMismatch)
+        }
+    }
+
+    /// 检查从寄存器 `reg` 开始的 `num` 个寄存器是否完全位于区域 `[min, max]` 中，返回起始偏移。
+    ///
+    /// 范围的末尾以 `usize` 计算，因此结束于 `0xFFFF` 的范围不会因 `u16` 溢出而被拒绝。
+    fn span_in(reg: u16, num: usize, min: u16, max: u16) -> Result<u16, RegError> {
+        let offset = Self::offset_in(reg, min, max)?;
+        if reg as usize + num > max as usize + 1 {
+            return Err(RegError::OutOfRange);
         }
+        Ok(offset)
+    }
+
+    /// 计算单比特寄存器 `reg` 所在字的指针及其位掩码。
+    fn bit_location(&self, reg: u16) -> Result<(*mut usize, usize), RegError> {
+        const N: u16 = core::mem::size_of::<usize>() as u16 * 8;
+        let reg = Self::offset_in(reg, Self::BIT_REG_MIN, Self::BIT_REG_MAX)?;
+        let ptr = unsafe { self.mem.cast::<usize>().as_ptr().add((reg / N) as usize) };
+        Ok((ptr, 1 << (reg % N)))
+    }
+
+    /// 获取指定寄存器 `reg` 的单比特值，`reg` 不属于单比特区域时返回错误。
+    pub fn try_get_bit(&self, reg: u16) -> Result<bool, RegError> {
+        let (ptr, mask) = self.bit_location(reg)?;
+        unsafe { Ok(ptr.read() & mask != 0) }
     }
 
+    /// 清除指定寄存器 `reg` 的单比特值，`reg` 不属于单比特区域时返回错误。
+    pub fn try_clear_bit(&self, reg: u16) -> Result<(), RegError> {
+        let (ptr, mask) = self.bit_location(reg)?;
+        unsafe { *ptr &= !mask };
+        Ok(())
+    }
+
+    /// 设置指定寄存器 `reg` 的单比特值，`reg` 不属于单比特区域时返回错误。
+    pub fn try_set_bit(&self, reg: u16) -> Result<(), RegError> {
+        let (ptr, mask) = self.bit_location(reg)?;
+        unsafe { *ptr |= mask };
+        Ok(())
+    }
+
+    /// 获取指定寄存器 `reg` 的单比特值，`reg` 不属于单比特区域时返回 `false`。
+    pub fn get_bit(&self, reg: u16) -> bool {
+        self.try_get_bit(reg).unwrap_or(false)
+    }
+
+    /// 清除指定寄存器 `reg` 的单比特值，`reg` 不属于单比特区域时不做任何操作。
     pub fn clear_bit(&self, reg: u16) {
-        unsafe {
-            const N: u16 = std::mem::size_of::<usize>() as u16;
-            if reg <= Self::BIT_REG_MAX {
-                let offset = reg / N;
-                let val_ptr = self.mem.cast::<usize>().as_ptr().offset(offset as isize);
-                *val_ptr &= !(1 << (reg % N));
-            }
+        let _ = self.try_clear_bit(reg);
+    }
+
+    /// 区域 `region` 的最小及最大寄存器地址（含），寄存器中没有该区域时返回 `None`。
+    pub fn region_bounds(region: Region) -> Option<(u16, u16)> {
+        match region {
+            Region::Bit => Some((Self::BIT_REG_MIN, Self::BIT_REG_MAX)),
+            Region::Bits8 => Some((Self::BITS_8_REG_MIN, Self::BITS_8_REG_MAX)),
+            Region::Bits16 => Some((Self::BITS_16_REG_MIN, Self::BITS_16_REG_MAX)),
+            Region::Bits32 => Some((Self::BITS_32_REG_MIN, Self::BITS_32_REG_MAX)),
+            Region::Bits64 => Some((Self::BITS_64_REG_MIN, Self::BITS_64_REG_MAX)),
+            Region::Bits128 => None,
         }
     }
 
+    /// 区域 `region` 在存储中的字节偏移，寄存器中没有该区域时返回 `None`。
+    pub fn region_byte_offset(region: Region) -> Option<usize> {
+        let ofs = match region {
+            Region::Bit => 0,
+            Region::Bits8 => Self::BITS_8_REG_OFS,
+            Region::Bits16 => Self::BITS_16_REG_OFS,
+            Region::Bits32 => Self::BITS_32_REG_OFS,
+            Region::Bits64 => Self::BITS_64_REG_OFS,
+            Region::Bits128 => return None,
+        };
+        Some(ofs as usize * region.element_size())
+    }
+
+    /// 设置指定寄存器 `reg` 的单比特值，`reg` 不属于单比特区域时不做任何操作。
     pub fn set_bit(&self, reg: u16) {
-        unsafe {
-            const N: u16 = std::mem::size_of::<usize>() as u16;
-            if reg <= Self::BIT_REG_MAX {
-                let offset = reg / N;
-                let val_ptr = self.mem.cast::<usize>().as_ptr().offset(offset as isize);
-                *val_ptr |= 1 << (reg % N);
+        let _ = self.try_set_bit(reg);
+    }
+
+    /// 读取从寄存器 `start` 开始的 `count` 个单比特值，按 Modbus 顺序打包为字节。
+    ///
+    /// 每个字节容纳 8 个值，低位在前，末尾不足一个字节的部分以 `0` 填充；
+    /// 不属于单比特区域的寄存器读作 `0`。
+    pub fn get_bits_packed(&self, start: u16, count: u16) -> Vec<u8> {
+        let mut out = vec![0u8; (count as usize).div_ceil(8)];
+        for (i, reg) in (start..=u16::MAX).take(count as usize).enumerate() {
+            if self.get_bit(reg) {
+                out[i / 8] |= 1 << (i % 8);
+            }
+        }
+        out
+    }
+
+    /// 将按 Modbus 顺序打包的 `data` 写入从寄存器 `start` 开始的 `count` 个单比特值。
+    ///
+    /// `data` 不足 `count` 个值时只写入已提供的部分；不属于单比特区域的寄存器将被忽略。
+    pub fn set_bits_packed(&self, start: u16, count: u16, data: &[u8]) {
+        let n = (count as usize).min(data.len() * 8);
+        for (i, reg) in (start..=u16::MAX).take(n).enumerate() {
+            if data[i / 8] & (1 << (i % 8)) != 0 {
+                self.set_bit(reg);
+            } else {
+                self.clear_bit(reg);
             }
         }
     }
@@ -232,25 +378,172 @@ impl Registers {
     // }
 }
 
+impl Default for Registers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Registers {
+    fn drop(&mut self) {
+        unsafe {
+            let layout = Layout::from_size_align_unchecked(Self::BUFFER_SIZE, 4096);
+            dealloc(self.mem.as_ptr(), layout);
+        }
+    }
+}
+
+unsafe impl Send for Registers {}
+unsafe impl Sync for Registers {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn region_spans() {
+        let mut spans = Vec::new();
+        for region in Region::ALL {
+            let (Some((min, max)), Some(ofs)) = (
+                Registers::region_bounds(*region),
+                Registers::region_byte_offset(*region),
+            ) else {
+                assert_eq!(*region, Region::Bits128);
+                continue;
+            };
+            let num = max as usize - min as usize + 1;
+            let len = match region {
+                Region::Bit => num / 8,
+                _ => num * region.element_size(),
+            };
+            spans.push((min, max, ofs, ofs + len));
+        }
+        assert_eq!(spans.len(), 5);
+        assert_eq!((spans[0].0, spans[4].1), (0x0000, 0xFFFF));
+        for pair in spans.windows(2) {
+            assert_eq!(pair[0].1 + 1, pair[1].0);
+            assert!(pair[0].3 <= pair[1].2);
+        }
+        assert!(spans[4].3 <= Registers::BUFFER_SIZE);
+
+        let regs = Registers::new();
+        let (min, max) = Registers::region_bounds(Region::Bits32).unwrap();
+        regs.set_u32(max, 7);
+        assert_eq!(regs.get_u32(max), 7);
+        assert_eq!(regs.try_get_u32(min - 1), Err(RegError::RegionMismatch));
+    }
+
     #[test]
     fn bits() {
         let regs = Registers::new();
         for a in Registers::BIT_REG_MIN..=Registers::BIT_REG_MAX {
             regs.set_bit(a);
-            assert_eq!(regs.get_bit(a), true);
+            assert!(regs.get_bit(a));
             regs.clear_bit(a);
-            assert_eq!(regs.get_bit(a), false);
+            assert!(!regs.get_bit(a));
         }
         for a in Registers::BITS_8_REG_MIN..=0xFFFF {
             regs.set_bit(a);
-            assert_eq!(regs.get_bit(a), false);
+            assert!(!regs.get_bit(a));
         }
     }
 
+    #[test]
+    fn bits_packed() {
+        let regs = Registers::new();
+        regs.set_bits_packed(0x0013, 10, &[0xCD, 0xFF]);
+        assert_eq!(regs.get_bits_packed(0x0013, 10), [0xCD, 0x03]);
+        assert!(!regs.get_bit(0x0013 + 10));
+        assert_eq!(regs.get_bits_packed(0x0013, 8), [0xCD]);
+        assert_eq!(regs.get_bits_packed(0x0014, 3), [0x06]);
+        assert!(regs.get_bits_packed(0x0000, 0).is_empty());
+        assert_eq!(regs.get_bits_packed(0x1FFE, 4), [0x00]);
+        regs.set_bits_packed(0x1FFE, 4, &[0x0F]);
+        assert_eq!(regs.get_bits_packed(0x1FFE, 4), [0x03]);
+    }
+
+    #[test]
+    fn fallible() {
+        let regs = Registers::new();
+        assert_eq!(regs.try_set_bit(Registers::BIT_REG_MAX), Ok(()));
+        assert_eq!(regs.try_get_bit(Registers::BIT_REG_MAX), Ok(true));
+        assert_eq!(
+            regs.try_set_bit(Registers::BITS_8_REG_MIN),
+            Err(RegError::RegionMismatch)
+        );
+        assert_eq!(regs.try_set_u16(Registers::BITS_16_REG_MAX, 0x1234), Ok(()));
+        assert_eq!(regs.try_get_u16(Registers::BITS_16_REG_MAX), Ok(0x1234));
+        assert_eq!(
+            regs.try_set_u16(Registers::BITS_32_REG_MIN, 0x1234),
+            Err(RegError::RegionMismatch)
+        );
+        assert_eq!(
+            regs.try_get_u16(Registers::BITS_16_REG_MIN - 1),
+            Err(RegError::RegionMismatch)
+        );
+        assert_eq!(regs.try_get_u64(Registers::BITS_64_REG_MAX), Ok(0));
+        assert_eq!(regs.get_u32(Registers::BITS_32_REG_MIN), 0);
+    }
+
+    #[test]
+    fn values_boundaries() {
+        let regs = Registers::new();
+        regs.set_u8_values(Registers::BITS_8_REG_MAX - 1, &[1, 2]);
+        assert_eq!(
+            regs.get_u8_values(Registers::BITS_8_REG_MAX - 1, 2),
+            &[1, 2]
+        );
+        assert_eq!(regs.get_u8_values(Registers::BITS_8_REG_MAX, 1), &[2]);
+        assert!(regs.get_u8_values(Registers::BITS_8_REG_MAX, 2).is_empty());
+        assert_eq!(regs.get_u16(Registers::BITS_16_REG_MIN), 0);
+
+        regs.set_u64_values(Registers::BITS_64_REG_MAX - 2, &[3, 4, 5]);
+        assert_eq!(
+            regs.get_u64_values(Registers::BITS_64_REG_MAX - 2, 3),
+            &[3, 4, 5]
+        );
+        assert_eq!(regs.get_u64_values(Registers::BITS_64_REG_MAX, 1), &[5]);
+        assert!(regs
+            .get_u64_values(Registers::BITS_64_REG_MAX, 2)
+            .is_empty());
+        assert!(regs
+            .get_u64_values(Registers::BITS_64_REG_MAX, u16::MAX)
+            .is_empty());
+
+        assert_eq!(regs.set_u64_values(Registers::BITS_64_REG_MAX, &[6, 7]), 1);
+        assert_eq!(regs.get_u64(Registers::BITS_64_REG_MAX), 6);
+        assert_eq!(regs.set_u64_values(Registers::BITS_32_REG_MAX, &[8]), 0);
+    }
+
+    #[test]
+    fn values_oversized() {
+        let regs = Registers::new();
+        regs.set_u32(Registers::BITS_32_REG_MIN, 0xC0FF_EE00);
+        regs.set_u16(Registers::BITS_16_REG_MAX, 0x5A5A);
+        let big = vec![0xFFFFu16; 0x1_0002];
+        assert_eq!(regs.set_u16_values(Registers::BITS_16_REG_MAX - 1, &big), 2);
+        assert_eq!(
+            regs.set_u16_values(Registers::BITS_16_REG_MAX, &big[..100]),
+            1
+        );
+        assert_eq!(regs.get_u16(Registers::BITS_16_REG_MAX), 0xFFFF);
+        assert_eq!(regs.get_u32(Registers::BITS_32_REG_MIN), 0xC0FF_EE00);
+
+        let ramp: Vec<u16> = (0..0x1_0002).map(|i| i as u16).collect();
+        assert_eq!(
+            regs.set_u16_values(Registers::BITS_16_REG_MIN, &ramp),
+            Registers::BITS_16_REG_NUM as usize
+        );
+        assert_eq!(regs.get_u16(Registers::BITS_16_REG_MAX), 0x3FFF);
+        assert_eq!(regs.get_u32(Registers::BITS_32_REG_MIN), 0xC0FF_EE00);
+
+        assert_eq!(
+            regs.set_u64_values(Registers::BITS_64_REG_MAX, &[u64::MAX; 0x1_0001]),
+            1
+        );
+        assert_eq!(regs.get_u64(Registers::BITS_64_REG_MAX), u64::MAX);
+    }
+
     #[test]
     fn bits_8() {
         let regs = Registers::new();
@@ -267,19 +560,27 @@ mod tests {
             assert_eq!(regs.get_u8(a), 0x00);
         }
     }
-}
 
-fn main() {
-    let regs = Registers::new();
-    regs.set_bit(0x0000);
-    println!("0x0000={}", regs.get_bit(0x0000));
-    println!("0x0010={}", regs.get_bit(0x1000));
-    regs.set_bit(0x010);
-    println!("0x0010={}", regs.get_bit(0x1000));
-    regs.set_i16_values(
-        Registers::BITS_16_REG_MIN + 0x0010,
-        &[1, 2, 3, 4, 5, 6, 7, 8, 9],
-    );
-    let s = regs.get_i16_values(Registers::BITS_16_REG_MIN + 0x0010, 16);
-    println!("{:?}", s);
+    #[test]
+    fn values_checked() {
+        let regs = Registers::new();
+        regs.set_u16_values(Registers::BITS_16_REG_MAX - 1, &[1, 2]);
+        assert_eq!(
+            regs.get_u16_values_checked(Registers::BITS_16_REG_MIN, 4),
+            Ok(&[0u16; 4][..])
+        );
+        assert_eq!(
+            regs.get_u16_values_checked(Registers::BITS_16_REG_MAX - 1, 8),
+            Ok(&[1u16, 2][..])
+        );
+        assert_eq!(
+            regs.get_u16_values_checked(Registers::BITS_32_REG_MIN, 1),
+            Err(RegError::RegionMismatch)
+        );
+        assert_eq!(
+            regs.get_u64_values_checked(Registers::BITS_64_REG_MAX, u16::MAX)
+                .map(<[u64]>::len),
+            Ok(1)
+        );
+    }
 }
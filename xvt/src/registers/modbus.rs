@@ -0,0 +1,829 @@
+use super::{RegError, Registers};
+use crate::Region;
+use alloc::vec::Vec;
+use core::sync::atomic::Ordering;
+
+/// 一个代表 Modbus 异常码的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ExceptionCode {
+    /// 不支持的功能码。
+    IllegalFunction = 0x01,
+    /// 访问的地址不属于对应区域。
+    IllegalDataAddress = 0x02,
+    /// 数量、字节数或取值不合法。
+    IllegalDataValue = 0x03,
+    /// 从站执行请求时发生不可恢复的错误。
+    ServerDeviceFailure = 0x04,
+}
+
+/// 寄存器访问错误都表示地址不属于对应区域或超出了区域末尾。
+impl From<RegError> for ExceptionCode {
+    fn from(_: RegError) -> Self {
+        ExceptionCode::IllegalDataAddress
+    }
+}
+
+/// 生成功能码 `function_code` 的异常响应 PDU：最高位置位的功能码及异常码 `code`。
+pub fn modbus_exception(function_code: u8, code: ExceptionCode) -> [u8; 2] {
+    [function_code | 0x80, code as u8]
+}
+
+/// 一个代表一次多寄存器读取请求的类型，由 [`plan_reads`] 生成。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadRequest {
+    /// 起始地址。
+    pub start: u16,
+    /// 读取的寄存器个数。
+    pub count: u16,
+}
+
+/// 将要读取的地址 `addrs` 合并为尽量少的多寄存器读取请求。
+///
+/// 地址先排序去重，相邻两个地址之间未被请求的寄存器不超过 `max_gap` 个时合并到同一请求，
+/// 每个请求最多覆盖 `max_span` 个寄存器（为 `0` 时按 `1` 处理）。返回的请求按起始地址升序排列。
+///
+/// # Examples
+///
+/// ```
+/// use xvt::{plan_reads, ReadRequest};
+///
+/// let plan = plan_reads(&[0x0012, 0x0010, 0x0100], 4, 125);
+/// assert_eq!(
+///     plan,
+///     [
+///         ReadRequest { start: 0x0010, count: 3 },
+///         ReadRequest { start: 0x0100, count: 1 },
+///     ]
+/// );
+/// ```
+pub fn plan_reads(addrs: &[u16], max_gap: u16, max_span: u16) -> Vec<ReadRequest> {
+    let mut sorted = addrs.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let max_span = max_span.max(1) as u32;
+
+    let mut plan: Vec<ReadRequest> = Vec::new();
+    for addr in sorted {
+        if let Some(last) = plan.last_mut() {
+            let end = last.start as u32 + last.count as u32;
+            let gap = addr as u32 - end;
+            let count = addr as u32 - last.start as u32 + 1;
+            if gap <= max_gap as u32 && count <= max_span {
+                last.count = count as u16;
+                continue;
+            }
+        }
+        plan.push(ReadRequest {
+            start: addr,
+            count: 1,
+        });
+    }
+    plan
+}
+
+/// Modbus TCP MBAP 报文头长度。
+const MBAP_LEN: usize = 7;
+
+/// 计算 `data` 的 Modbus CRC-16 校验值（多项式 `0xA001`，初值 `0xFFFF`）。
+///
+/// 在 RTU 帧中，校验值以低字节在前的顺序附加在帧尾。
+pub fn modbus_crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for b in data {
+        crc ^= *b as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xA001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// 读取第 `i` 个字节开始的大端 `u16`。
+fn be_u16(data: &[u8], i: usize) -> u16 {
+    u16::from_be_bytes([data[i], data[i + 1]])
+}
+
+impl Registers {
+    /// 解析并执行一个 Modbus 请求 PDU，返回响应 PDU。
+    ///
+    /// Modbus 地址直接对应寄存器地址：线圈位于单比特区域，保持寄存器位于 16 位区域，
+    /// 寄存器值在 PDU 中以大端字节序传输。
+    ///
+    /// # 功能码
+    ///
+    /// * `0x01` - 读线圈。
+    /// * `0x02` - 读离散输入，与读线圈访问同一单比特区域。
+    /// * `0x03` - 读保持寄存器。
+    /// * `0x04` - 读输入寄存器，与保持寄存器使用相同的地址、但存放在独立的存储中，
+    ///   参见 [`Registers::set_input_register`]。
+    /// * `0x05` - 写单个线圈。
+    /// * `0x06` - 写单个寄存器。
+    /// * `0x0F` - 写多个线圈。
+    /// * `0x10` - 写多个寄存器。
+    /// * `0x17` - 读写多个寄存器，先写后读。
+    ///
+    /// 不支持的功能码返回异常码 `0x01`，地址不属于对应区域返回异常码 `0x02`，
+    /// 数量、字节数或取值不合法返回异常码 `0x03`，写入只读区域（参见
+    /// [`Registers::set_region_read_only`]）返回异常码 `0x02`；出现异常时寄存器保持不变。
+    /// `pdu` 为空时无法确定功能码，返回空响应。
+    pub fn apply_pdu(&self, pdu: &[u8]) -> Vec<u8> {
+        let Some((&func, data)) = pdu.split_first() else {
+            return Vec::new();
+        };
+        let result = match func {
+            0x01 | 0x02 => self.read_coils(data),
+            0x03 => self.read_holding_registers(data),
+            0x04 => self.read_input_registers(data),
+            0x05 => self.write_single_coil(data),
+            0x06 => self.write_single_register(data),
+            0x0F => self.write_multiple_coils(data),
+            0x10 => self.write_multiple_registers(data),
+            0x17 => self.read_write_multiple_registers(data),
+            _ => Err(ExceptionCode::IllegalFunction),
+        };
+        match result {
+            Ok(body) => {
+                let mut out = Vec::with_capacity(body.len() + 1);
+                out.push(func);
+                out.extend_from_slice(&body);
+                out
+            }
+            Err(code) => modbus_exception(func, code).to_vec(),
+        }
+    }
+
+    /// 解析并执行一个 Modbus TCP 请求帧，返回响应帧。
+    ///
+    /// 请求帧由 7 字节的 MBAP 报文头（事务标识、协议标识、长度、单元标识）和 PDU 组成，
+    /// 响应帧回显事务标识和单元标识，并按响应 PDU 重新计算长度。报文头不完整、协议标识
+    /// 不为 `0` 或 PDU 为空时返回 `None`；声明的长度超出帧的实际长度时，只处理实际收到的部分。
+    pub fn handle_tcp_frame(&self, frame: &[u8]) -> Option<Vec<u8>> {
+        if frame.len() < MBAP_LEN || be_u16(frame, 2) != 0 {
+            return None;
+        }
+        let len = (be_u16(frame, 4) as usize).checked_sub(1)?;
+        let end = frame.len().min(MBAP_LEN + len);
+        let pdu = self.apply_pdu(&frame[MBAP_LEN..end]);
+        if pdu.is_empty() {
+            return None;
+        }
+        let mut out = Vec::with_capacity(MBAP_LEN + pdu.len());
+        out.extend_from_slice(&frame[0..4]);
+        out.extend_from_slice(&(pdu.len() as u16 + 1).to_be_bytes());
+        out.push(frame[6]);
+        out.extend_from_slice(&pdu);
+        Some(out)
+    }
+
+    /// 解析并执行一个 Modbus RTU 请求帧，返回响应帧。
+    ///
+    /// 请求帧由从站地址、PDU 和 CRC-16 组成，响应帧回显从站地址并附加重新计算的 CRC-16。
+    /// 帧长度不足 4 字节或校验值不匹配时返回 `None`。
+    pub fn handle_rtu_frame(&self, frame: &[u8]) -> Option<Vec<u8>> {
+        if frame.len() < 4 {
+            return None;
+        }
+        let (body, crc) = frame.split_at(frame.len() - 2);
+        if modbus_crc16(body) != u16::from_le_bytes([crc[0], crc[1]]) {
+            return None;
+        }
+        let pdu = self.apply_pdu(&body[1..]);
+        let mut out = Vec::with_capacity(pdu.len() + 3);
+        out.push(body[0]);
+        out.extend_from_slice(&pdu);
+        out.extend_from_slice(&modbus_crc16(&out).to_le_bytes());
+        Some(out)
+    }
+
+    /// 设置区域 `region` 对 Modbus 客户端是否只读。
+    ///
+    /// 只读的单比特区域相当于离散输入，只读的 16 位区域即只读的保持寄存器：[`Registers::apply_pdu`]
+    /// 拒绝写入这些区域，设备端仍可以通过 [`Registers::set_discrete_input`] 或其他 `set_*`
+    /// 方法更新它们。
+    pub fn set_region_read_only(&self, region: Region, read_only: bool) {
+        let mask = 1 << region as u8;
+        if read_only {
+            self.read_only.fetch_or(mask, Ordering::Relaxed);
+        } else {
+            self.read_only.fetch_and(!mask, Ordering::Relaxed);
+        }
+    }
+
+    /// 区域 `region` 对 Modbus 客户端是否只读。
+    pub fn is_region_read_only(&self, region: Region) -> bool {
+        self.read_only.load(Ordering::Relaxed) & (1 << region as u8) != 0
+    }
+
+    /// 由设备端设置离散输入 `reg` 的值，即使单比特区域对客户端只读。
+    pub fn set_discrete_input(&self, reg: u16, val: bool) -> Result<(), RegError> {
+        if val {
+            self.try_set_bit(reg)
+        } else {
+            self.try_clear_bit(reg)
+        }
+    }
+
+    /// 由设备端设置输入寄存器 `reg` 的值。
+    ///
+    /// 输入寄存器与保持寄存器使用相同的地址范围（16 位区域），但存放在独立的存储中：
+    /// 写入保持寄存器不影响同一地址的输入寄存器，反之亦然。客户端只能通过功能码 `0x04` 读取。
+    pub fn set_input_register(&self, reg: u16, val: u16) -> Result<(), RegError> {
+        let i = Self::offset_in(reg, Self::BITS_16_REG_MIN, Self::BITS_16_REG_MAX)?;
+        self.inputs[i as usize].store(val, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// 获取输入寄存器 `reg` 的值，参见 [`Registers::set_input_register`]。
+    pub fn get_input_register(&self, reg: u16) -> Result<u16, RegError> {
+        let i = Self::offset_in(reg, Self::BITS_16_REG_MIN, Self::BITS_16_REG_MAX)?;
+        Ok(self.inputs[i as usize].load(Ordering::Relaxed))
+    }
+
+    /// 以大端字节序返回从寄存器 `start` 开始的 `count` 个保持寄存器，共 `count * 2` 字节，
+    /// 可直接作为读保持寄存器响应中字节数之后的数据。
+    ///
+    /// `start` 不属于 16 位区域时返回 [`RegError::RegionMismatch`]，范围越过区域末尾时返回
+    /// [`RegError::OutOfRange`]，而不是返回较短的数据。
+    pub fn read_registers_be(&self, start: u16, count: u16) -> Result<Vec<u8>, RegError> {
+        Self::span_in(
+            start,
+            count as usize,
+            Self::BITS_16_REG_MIN,
+            Self::BITS_16_REG_MAX,
+        )?;
+        let mut out = Vec::with_capacity(count as usize * 2);
+        for val in self.get_u16_values(start, count) {
+            out.extend_from_slice(&val.to_be_bytes());
+        }
+        Ok(out)
+    }
+
+    /// 先将 `write_values` 写入从寄存器 `write_start` 开始的保持寄存器，再读取从 `read_start`
+    /// 开始的 `read_count` 个寄存器，对应 Modbus 功能码 `0x17`。
+    ///
+    /// 两个读写范围可以重叠，读取结果反映本次写入的值。任一范围不完全位于 16 位区域时
+    /// 不做任何写入并返回空列表。
+    pub fn read_write_multiple_u16(
+        &self,
+        read_start: u16,
+        read_count: u16,
+        write_start: u16,
+        write_values: &[u16],
+    ) -> Vec<u16> {
+        let (min, max) = (Self::BITS_16_REG_MIN, Self::BITS_16_REG_MAX);
+        if Self::span_in(read_start, read_count as usize, min, max).is_err()
+            || Self::span_in(write_start, write_values.len(), min, max).is_err()
+        {
+            return Vec::new();
+        }
+        self.set_u16_values(write_start, write_values);
+        self.get_u16_values(read_start, read_count).to_vec()
+    }
+
+    /// 检查区域 `region` 是否允许客户端写入。
+    fn writable(&self, region: Region) -> Result<(), ExceptionCode> {
+        if self.is_region_read_only(region) {
+            return Err(ExceptionCode::IllegalDataAddress);
+        }
+        Ok(())
+    }
+
+    fn read_coils(&self, data: &[u8]) -> Result<Vec<u8>, ExceptionCode> {
+        if data.len() != 4 {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        let (start, num) = (be_u16(data, 0), be_u16(data, 2));
+        if !(1..=2000).contains(&num) {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        Self::span_in(start, num as usize, Self::BIT_REG_MIN, Self::BIT_REG_MAX)?;
+        let bits = self.get_bits_packed(start, num);
+        let mut out = Vec::with_capacity(1 + bits.len());
+        out.push(bits.len() as u8);
+        out.extend_from_slice(&bits);
+        Ok(out)
+    }
+
+    fn read_holding_registers(&self, data: &[u8]) -> Result<Vec<u8>, ExceptionCode> {
+        if data.len() != 4 {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        let (start, num) = (be_u16(data, 0), be_u16(data, 2));
+        if !(1..=125).contains(&num) {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        let payload = self.read_registers_be(start, num)?;
+        let mut out = Vec::with_capacity(1 + payload.len());
+        out.push(payload.len() as u8);
+        out.extend_from_slice(&payload);
+        Ok(out)
+    }
+
+    fn read_input_registers(&self, data: &[u8]) -> Result<Vec<u8>, ExceptionCode> {
+        if data.len() != 4 {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        let (start, num) = (be_u16(data, 0), be_u16(data, 2));
+        if !(1..=125).contains(&num) {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        let offset = Self::span_in(
+            start,
+            num as usize,
+            Self::BITS_16_REG_MIN,
+            Self::BITS_16_REG_MAX,
+        )? as usize;
+        let mut out = Vec::with_capacity(1 + num as usize * 2);
+        out.push((num * 2) as u8);
+        for val in &self.inputs[offset..offset + num as usize] {
+            out.extend_from_slice(&val.load(Ordering::Relaxed).to_be_bytes());
+        }
+        Ok(out)
+    }
+
+    fn write_single_coil(&self, data: &[u8]) -> Result<Vec<u8>, ExceptionCode> {
+        if data.len() != 4 {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        let reg = be_u16(data, 0);
+        let set = match be_u16(data, 2) {
+            0xFF00 => true,
+            0x0000 => false,
+            _ => return Err(ExceptionCode::IllegalDataValue),
+        };
+        self.writable(Region::Bit)?;
+        if set {
+            self.try_set_bit(reg)?;
+        } else {
+            self.try_clear_bit(reg)?;
+        }
+        Ok(data.to_vec())
+    }
+
+    fn write_single_register(&self, data: &[u8]) -> Result<Vec<u8>, ExceptionCode> {
+        if data.len() != 4 {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        self.writable(Region::Bits16)?;
+        self.try_set_u16(be_u16(data, 0), be_u16(data, 2))?;
+        Ok(data.to_vec())
+    }
+
+    fn write_multiple_coils(&self, data: &[u8]) -> Result<Vec<u8>, ExceptionCode> {
+        if data.len() < 5 {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        let (start, num, count) = (be_u16(data, 0), be_u16(data, 2), data[4] as usize);
+        if !(1..=0x07B0).contains(&num)
+            || count != (num as usize).div_ceil(8)
+            || data.len() != 5 + count
+        {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        self.writable(Region::Bit)?;
+        Self::span_in(start, num as usize, Self::BIT_REG_MIN, Self::BIT_REG_MAX)?;
+        self.set_bits_packed(start, num, &data[5..]);
+        Ok(data[..4].to_vec())
+    }
+
+    fn write_multiple_registers(&self, data: &[u8]) -> Result<Vec<u8>, ExceptionCode> {
+        if data.len() < 5 {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        let (start, num, count) = (be_u16(data, 0), be_u16(data, 2), data[4] as usize);
+        if !(1..=0x7B).contains(&num) || count != num as usize * 2 || data.len() != 5 + count {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        self.writable(Region::Bits16)?;
+        Self::span_in(
+            start,
+            num as usize,
+            Self::BITS_16_REG_MIN,
+            Self::BITS_16_REG_MAX,
+        )?;
+        let vals: Vec<u16> = data[5..]
+            .chunks_exact(2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+            .collect();
+        self.set_u16_values(start, &vals);
+        Ok(data[..4].to_vec())
+    }
+
+    fn read_write_multiple_registers(&self, data: &[u8]) -> Result<Vec<u8>, ExceptionCode> {
+        if data.len() < 9 {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        let (read_start, read_num) = (be_u16(data, 0), be_u16(data, 2));
+        let (write_start, write_num, count) = (be_u16(data, 4), be_u16(data, 6), data[8] as usize);
+        if !(1..=0x7D).contains(&read_num)
+            || !(1..=0x79).contains(&write_num)
+            || count != write_num as usize * 2
+            || data.len() != 9 + count
+        {
+            return Err(ExceptionCode::IllegalDataValue);
+        }
+        self.writable(Region::Bits16)?;
+        let (min, max) = (Self::BITS_16_REG_MIN, Self::BITS_16_REG_MAX);
+        Self::span_in(read_start, read_num as usize, min, max)?;
+        Self::span_in(write_start, write_num as usize, min, max)?;
+        let vals: Vec<u16> = data[9..]
+            .chunks_exact(2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+            .collect();
+        let read = self.read_write_multiple_u16(read_start, read_num, write_start, &vals);
+        let mut out = Vec::with_capacity(1 + read.len() * 2);
+        out.push((read.len() * 2) as u8);
+        for val in read {
+            out.extend_from_slice(&val.to_be_bytes());
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn exception() {
+        let codes = [
+            (ExceptionCode::IllegalFunction, 0x01),
+            (ExceptionCode::IllegalDataAddress, 0x02),
+            (ExceptionCode::IllegalDataValue, 0x03),
+            (ExceptionCode::ServerDeviceFailure, 0x04),
+        ];
+        for (code, byte) in codes {
+            let pdu = modbus_exception(0x03, code);
+            assert_eq!(pdu, [0x83, byte]);
+            assert_ne!(pdu[0] & 0x80, 0);
+        }
+        assert_eq!(
+            modbus_exception(0x90, ExceptionCode::IllegalFunction),
+            [0x90, 0x01]
+        );
+        assert_eq!(
+            ExceptionCode::from(RegError::OutOfRange),
+            ExceptionCode::IllegalDataAddress
+        );
+    }
+
+    #[test]
+    fn read_only_regions() {
+        let regs = Registers::new();
+        regs.set_region_read_only(Region::Bit, true);
+        regs.set_region_read_only(Region::Bits16, true);
+        assert!(regs.is_region_read_only(Region::Bit));
+        assert!(!regs.is_region_read_only(Region::Bits8));
+
+        assert_eq!(
+            regs.apply_pdu(&[0x05, 0x00, 0x03, 0xFF, 0x00]),
+            [0x85, 0x02]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x0F, 0x00, 0x00, 0x00, 0x08, 0x01, 0xFF]),
+            [0x8F, 0x02]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x06, 0x80, 0x00, 0x12, 0x34]),
+            [0x86, 0x02]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x10, 0x80, 0x00, 0x00, 0x01, 0x02, 0x12, 0x34]),
+            [0x90, 0x02]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x05, 0x00, 0x03, 0x12, 0x34]),
+            [0x85, 0x03]
+        );
+        assert!(!regs.get_bit(0x0003));
+        assert_eq!(regs.get_u16(0x8000), 0);
+
+        regs.set_discrete_input(0x0003, true).unwrap();
+        regs.set_input_register(0x8000, 0xBEEF).unwrap();
+        assert_eq!(
+            regs.set_input_register(0x0000, 1),
+            Err(RegError::RegionMismatch)
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x02, 0x00, 0x00, 0x00, 0x08]),
+            [0x02, 0x01, 0x08]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x04, 0x80, 0x00, 0x00, 0x01]),
+            [0x04, 0x02, 0xBE, 0xEF]
+        );
+
+        regs.set_region_read_only(Region::Bit, false);
+        assert_eq!(
+            regs.apply_pdu(&[0x05, 0x00, 0x03, 0x00, 0x00]),
+            [0x05, 0x00, 0x03, 0x00, 0x00]
+        );
+        assert!(!regs.get_bit(0x0003));
+        assert!(regs.is_region_read_only(Region::Bits16));
+    }
+
+    #[test]
+    fn read_coils() {
+        let regs = Registers::new();
+        for (i, b) in [0xCDu8, 0x6B, 0x05].iter().enumerate() {
+            for j in 0..8 {
+                if b & (1 << j) != 0 {
+                    regs.set_bit(0x0013 + (i * 8 + j) as u16);
+                }
+            }
+        }
+        regs.set_bit(0x0013 + 19);
+        assert_eq!(
+            regs.apply_pdu(&[0x01, 0x00, 0x13, 0x00, 0x13]),
+            [0x01, 0x03, 0xCD, 0x6B, 0x05]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x01, 0x1F, 0xFF, 0x00, 0x02]),
+            [0x81, 0x02]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x01, 0x00, 0x00, 0x00, 0x00]),
+            [0x81, 0x03]
+        );
+    }
+
+    #[test]
+    fn read_holding_registers() {
+        let regs = Registers::new();
+        regs.set_u16_values(0x806B, &[0x022B, 0x0000, 0x0064]);
+        assert_eq!(
+            regs.apply_pdu(&[0x03, 0x80, 0x6B, 0x00, 0x03]),
+            [0x03, 0x06, 0x02, 0x2B, 0x00, 0x00, 0x00, 0x64]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x03, 0x00, 0x6B, 0x00, 0x03]),
+            [0x83, 0x02]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x03, 0xBF, 0xFF, 0x00, 0x02]),
+            [0x83, 0x02]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x03, 0x80, 0x00, 0x00, 0x7E]),
+            [0x83, 0x03]
+        );
+    }
+
+    #[test]
+    fn write_single_coil() {
+        let regs = Registers::new();
+        let req = [0x05, 0x00, 0xAC, 0xFF, 0x00];
+        assert_eq!(regs.apply_pdu(&req), req);
+        assert!(regs.get_bit(0x00AC));
+        let req = [0x05, 0x00, 0xAC, 0x00, 0x00];
+        assert_eq!(regs.apply_pdu(&req), req);
+        assert!(!regs.get_bit(0x00AC));
+        assert_eq!(
+            regs.apply_pdu(&[0x05, 0x00, 0xAC, 0x12, 0x34]),
+            [0x85, 0x03]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x05, 0x20, 0x00, 0xFF, 0x00]),
+            [0x85, 0x02]
+        );
+    }
+
+    #[test]
+    fn write_single_register() {
+        let regs = Registers::new();
+        let req = [0x06, 0x80, 0x01, 0x00, 0x03];
+        assert_eq!(regs.apply_pdu(&req), req);
+        assert_eq!(regs.get_u16(0x8001), 0x0003);
+        assert_eq!(
+            regs.apply_pdu(&[0x06, 0x00, 0x01, 0x00, 0x03]),
+            [0x86, 0x02]
+        );
+    }
+
+    #[test]
+    fn write_multiple_coils() {
+        let regs = Registers::new();
+        assert_eq!(
+            regs.apply_pdu(&[0x0F, 0x00, 0x13, 0x00, 0x0A, 0x02, 0xCD, 0x01]),
+            [0x0F, 0x00, 0x13, 0x00, 0x0A]
+        );
+        let bits: Vec<bool> = (0x0013..0x001D).map(|a| regs.get_bit(a)).collect();
+        assert_eq!(
+            bits,
+            [true, false, true, true, false, false, true, true, true, false]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x0F, 0x00, 0x13, 0x00, 0x0A, 0x01, 0xCD]),
+            [0x8F, 0x03]
+        );
+    }
+
+    #[test]
+    fn write_multiple_registers() {
+        let regs = Registers::new();
+        assert_eq!(
+            regs.apply_pdu(&[0x10, 0x80, 0x01, 0x00, 0x02, 0x04, 0x00, 0x0A, 0x01, 0x02]),
+            [0x10, 0x80, 0x01, 0x00, 0x02]
+        );
+        assert_eq!(regs.get_u16_values(0x8001, 2), &[0x000A, 0x0102]);
+        assert_eq!(
+            regs.apply_pdu(&[0x10, 0xBF, 0xFF, 0x00, 0x02, 0x04, 0x00, 0x0A, 0x01, 0x02]),
+            [0x90, 0x02]
+        );
+        assert_eq!(regs.get_u16(0xBFFF), 0);
+    }
+
+    #[test]
+    fn tcp_frame() {
+        let regs = Registers::new();
+        regs.set_u16(0x8000, 0x1234);
+        let req = [
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x11, 0x03, 0x80, 0x00, 0x00, 0x01,
+        ];
+        assert_eq!(
+            regs.handle_tcp_frame(&req).unwrap(),
+            [0x00, 0x01, 0x00, 0x00, 0x00, 0x05, 0x11, 0x03, 0x02, 0x12, 0x34]
+        );
+        let req = [
+            0x12, 0x34, 0x00, 0x00, 0x00, 0x06, 0x01, 0x06, 0x80, 0x02, 0xAB, 0xCD,
+        ];
+        assert_eq!(regs.handle_tcp_frame(&req).unwrap(), req);
+        assert_eq!(regs.get_u16(0x8002), 0xABCD);
+        let req = [
+            0x00, 0x02, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x01,
+        ];
+        assert_eq!(
+            regs.handle_tcp_frame(&req).unwrap(),
+            [0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x01, 0x83, 0x02]
+        );
+    }
+
+    #[test]
+    fn tcp_frame_malformed() {
+        let regs = Registers::new();
+        assert_eq!(regs.handle_tcp_frame(&[0x00, 0x01, 0x00, 0x00, 0x00]), None);
+        let req = [
+            0x00, 0x01, 0x00, 0x01, 0x00, 0x06, 0x01, 0x03, 0x80, 0x00, 0x00, 0x01,
+        ];
+        assert_eq!(regs.handle_tcp_frame(&req), None);
+        assert_eq!(
+            regs.handle_tcp_frame(&[0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x01]),
+            None
+        );
+        assert_eq!(
+            regs.handle_tcp_frame(&[0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x01]),
+            None
+        );
+        // 声明长度远超实际长度，只处理已收到的部分。
+        let req = [
+            0x00, 0x03, 0x00, 0x00, 0xFF, 0xFF, 0x01, 0x03, 0x80, 0x00, 0x00, 0x01,
+        ];
+        assert_eq!(
+            regs.handle_tcp_frame(&req).unwrap(),
+            [0x00, 0x03, 0x00, 0x00, 0x00, 0x05, 0x01, 0x03, 0x02, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn crc16() {
+        assert_eq!(modbus_crc16(b"123456789"), 0x4B37);
+        assert_eq!(modbus_crc16(&[0x01, 0x03, 0x00, 0x00, 0x00, 0x0A]), 0xCDC5);
+        assert_eq!(modbus_crc16(&[]), 0xFFFF);
+    }
+
+    #[test]
+    fn rtu_frame() {
+        let regs = Registers::new();
+        regs.set_u16_values(0x8000, &[0x0001, 0x0002]);
+        let req = [0x11, 0x03, 0x80, 0x00, 0x00, 0x02];
+        let mut frame = req.to_vec();
+        frame.extend_from_slice(&modbus_crc16(&req).to_le_bytes());
+        let resp = regs.handle_rtu_frame(&frame).unwrap();
+        assert_eq!(resp[..7], [0x11, 0x03, 0x04, 0x00, 0x01, 0x00, 0x02]);
+        assert_eq!(modbus_crc16(&resp[..7]).to_le_bytes(), resp[7..]);
+
+        let req = [0x01, 0x05, 0x00, 0xAC, 0xFF, 0x00, 0x4C, 0x1B];
+        assert_eq!(regs.handle_rtu_frame(&req).unwrap(), req);
+        assert!(regs.get_bit(0x00AC));
+    }
+
+    #[test]
+    fn rtu_frame_malformed() {
+        let regs = Registers::new();
+        assert_eq!(regs.handle_rtu_frame(&[0x01, 0x03, 0x00]), None);
+        let req = [0x01, 0x06, 0x80, 0x00, 0x00, 0x01, 0x00, 0x00];
+        assert_eq!(regs.handle_rtu_frame(&req), None);
+        assert_eq!(regs.get_u16(0x8000), 0);
+    }
+
+    #[test]
+    fn unsupported() {
+        let regs = Registers::new();
+        assert_eq!(regs.apply_pdu(&[0x2B, 0x0E, 0x01, 0x00]), [0xAB, 0x01]);
+        assert_eq!(regs.apply_pdu(&[0x03, 0x80]), [0x83, 0x03]);
+        assert!(regs.apply_pdu(&[]).is_empty());
+    }
+
+    #[test]
+    fn read_plan() {
+        let req = |start, count| ReadRequest { start, count };
+        assert_eq!(plan_reads(&[0x0011, 0x0010], 0, 125), [req(0x0010, 2)]);
+        assert_eq!(plan_reads(&[0x0010, 0x0013], 2, 125), [req(0x0010, 4)]);
+        assert_eq!(
+            plan_reads(&[0x0010, 0x0014], 2, 125),
+            [req(0x0010, 1), req(0x0014, 1)]
+        );
+        assert_eq!(
+            plan_reads(&[0, 1, 2, 3, 4, 3], 0, 3),
+            [req(0, 3), req(3, 2)]
+        );
+        assert_eq!(
+            plan_reads(&[0xFFFF, 0], 0xFFFF, 0),
+            [req(0, 1), req(0xFFFF, 1)]
+        );
+        assert!(plan_reads(&[], 0, 125).is_empty());
+    }
+
+    #[test]
+    fn read_write_multiple() {
+        let regs = Registers::new();
+        let base = Registers::BITS_16_REG_MIN;
+        regs.set_u16_values(base, &[1, 2, 3, 4]);
+        assert_eq!(
+            regs.read_write_multiple_u16(base, 4, base + 1, &[20, 30]),
+            [1, 20, 30, 4]
+        );
+        assert!(regs
+            .read_write_multiple_u16(base, 1, Registers::BITS_16_REG_MAX, &[9, 9])
+            .is_empty());
+        assert!(regs
+            .read_write_multiple_u16(Registers::BITS_32_REG_MIN, 1, base, &[9])
+            .is_empty());
+        assert_eq!(regs.get_u16(base), 1);
+
+        let mut pdu = vec![0x17, 0x80, 0x00, 0x00, 0x02, 0x80, 0x01, 0x00, 0x01, 0x02];
+        pdu.extend_from_slice(&0xBEEFu16.to_be_bytes());
+        assert_eq!(regs.apply_pdu(&pdu), [0x17, 0x04, 0x00, 0x01, 0xBE, 0xEF]);
+        pdu[6] = 0x00;
+        pdu[5] = 0xC0;
+        assert_eq!(regs.apply_pdu(&pdu), [0x97, 0x02]);
+        regs.set_region_read_only(Region::Bits16, true);
+        pdu[5] = 0x80;
+        assert_eq!(regs.apply_pdu(&pdu), [0x97, 0x02]);
+    }
+
+    #[test]
+    fn registers_be() {
+        let regs = Registers::new();
+        let base = Registers::BITS_16_REG_MIN;
+        regs.set_u16_values(base, &[0x1234, 0xABCD]);
+        assert_eq!(
+            regs.read_registers_be(base, 2),
+            Ok(vec![0x12, 0x34, 0xAB, 0xCD])
+        );
+        assert_eq!(regs.read_registers_be(base, 0), Ok(vec![]));
+        assert_eq!(
+            regs.read_registers_be(Registers::BITS_16_REG_MAX, 2),
+            Err(RegError::OutOfRange)
+        );
+        assert_eq!(
+            regs.read_registers_be(Registers::BIT_REG_MIN, 1),
+            Err(RegError::RegionMismatch)
+        );
+    }
+
+    #[test]
+    fn input_registers() {
+        let regs = Registers::new();
+        regs.set_u16(0x8001, 0x1111);
+        regs.set_input_register(0x8001, 0x2222).unwrap();
+        assert_eq!(
+            regs.apply_pdu(&[0x06, 0x80, 0x01, 0x33, 0x33]),
+            [0x06, 0x80, 0x01, 0x33, 0x33]
+        );
+        assert_eq!(regs.get_input_register(0x8001), Ok(0x2222));
+        assert_eq!(regs.get_u16(0x8001), 0x3333);
+        assert_eq!(
+            regs.apply_pdu(&[0x04, 0x80, 0x00, 0x00, 0x02]),
+            [0x04, 0x04, 0x00, 0x00, 0x22, 0x22]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x03, 0x80, 0x00, 0x00, 0x02]),
+            [0x03, 0x04, 0x00, 0x00, 0x33, 0x33]
+        );
+        assert_eq!(
+            regs.apply_pdu(&[0x04, 0xBF, 0xFF, 0x00, 0x02]),
+            [0x84, 0x02]
+        );
+        assert_eq!(
+            regs.get_input_register(0xC000),
+            Err(RegError::RegionMismatch)
+        );
+    }
+}
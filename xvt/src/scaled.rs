@@ -0,0 +1,67 @@
+use crate::ValueTable;
+
+impl ValueTable {
+    /// 获取指定地址 `addr` 类型为 `u16` 的原始值，并按 `raw * scale + offset` 换算为工程值。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xvt::ValueTable;
+    ///
+    /// let vt = ValueTable::new();
+    /// vt.set_scaled_u16(0x0001, 23.4, 0.1, 0.0);
+    /// assert_eq!(vt.get_u16(0x0001), 234);
+    /// assert!((vt.get_scaled_u16(0x0001, 0.1, 0.0) - 23.4).abs() < 1e-9);
+    /// ```
+    pub fn get_scaled_u16(&self, addr: u16, scale: f64, offset: f64) -> f64 {
+        self.get_u16(addr) as f64 * scale + offset
+    }
+
+    /// 将工程值 `engineering` 按 `(engineering - offset) / scale` 换算为原始值，
+    /// 四舍五入后写入指定地址 `addr` 类型为 `u16` 的值。
+    ///
+    /// 换算结果超出 `u16` 范围时取最接近的边界值，结果为 NaN（例如 `scale` 为 `0` 且
+    /// `engineering` 等于 `offset`）时写入 `0`。
+    pub fn set_scaled_u16(&self, addr: u16, engineering: f64, scale: f64, offset: f64) {
+        let raw = (engineering - offset) / scale;
+        // `as` 转换对超出范围的值饱和、对 NaN 取 0，先加 0.5 实现对非负数的四舍五入。
+        let raw = if raw > 0.0 { (raw + 0.5) as u16 } else { 0 };
+        self.set_u16(addr, raw);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typical_scales() {
+        let vt = ValueTable::new();
+        vt.set_scaled_u16(0x0000, 12.34, 0.1, 0.0);
+        assert_eq!(vt.get_u16(0x0000), 123);
+        vt.set_scaled_u16(0x0000, 12.36, 0.1, 0.0);
+        assert_eq!(vt.get_u16(0x0000), 124);
+        assert!((vt.get_scaled_u16(0x0000, 0.1, 0.0) - 12.4).abs() < 1e-9);
+
+        vt.set_scaled_u16(0x0001, 250_000.0, 100.0, 0.0);
+        assert_eq!(vt.get_u16(0x0001), 2500);
+        assert_eq!(vt.get_scaled_u16(0x0001, 100.0, 0.0), 250_000.0);
+
+        vt.set_scaled_u16(0x0002, -20.0, 0.1, -40.0);
+        assert_eq!(vt.get_u16(0x0002), 200);
+        assert_eq!(vt.get_scaled_u16(0x0002, 0.5, -40.0), 60.0);
+    }
+
+    #[test]
+    fn clamps() {
+        let vt = ValueTable::new();
+        vt.set_scaled_u16(0x0000, 10_000.0, 0.1, 0.0);
+        assert_eq!(vt.get_u16(0x0000), u16::MAX);
+        vt.set_scaled_u16(0x0000, -5.0, 0.1, 0.0);
+        assert_eq!(vt.get_u16(0x0000), 0);
+        vt.set_scaled_u16(0x0000, f64::INFINITY, 100.0, 0.0);
+        assert_eq!(vt.get_u16(0x0000), u16::MAX);
+        vt.set_scaled_u16(0x0000, f64::NAN, 100.0, 0.0);
+        assert_eq!(vt.get_u16(0x0000), 0);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::ValueTable;
+use core::sync::atomic::{fence, Ordering};
+
+/// 等待其他线程完成写入时让出处理器。
+#[inline]
+fn relax() {
+    #[cfg(feature = "std")]
+    std::thread::yield_now();
+    #[cfg(not(feature = "std"))]
+    core::hint::spin_loop();
+}
+
+impl ValueTable {
+    /// 以版本号保护的方式执行一组写入 `f`，配合 [`ValueTable::read_consistent`] 使用。
+    ///
+    /// 写入前后各递增一次内部版本号，写入期间版本号为奇数；多个线程同时调用时依次执行。
+    /// 只有在 `f` 中完成的写入才受到保护。
+    pub fn write_consistent<T>(&self, f: impl FnOnce(&ValueTable) -> T) -> T {
+        let mut v = self.version.load(Ordering::Relaxed);
+        loop {
+            if v & 1 == 0 {
+                match self.version.compare_exchange_weak(
+                    v,
+                    v + 1,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => break,
+                    Err(cur) => v = cur,
+                }
+            } else {
+                relax();
+                v = self.version.load(Ordering::Relaxed);
+            }
+        }
+        fence(Ordering::Release);
+        let r = f(self);
+        self.version.store(v + 2, Ordering::Release);
+        r
+    }
+
+    /// 执行只读操作 `f` 并返回其结果，保证结果不会观察到 [`ValueTable::write_consistent`] 写入的中间状态。
+    ///
+    /// 读取不加锁，若 `f` 执行期间有写入发生则重试，因此 `f` 可能被调用多次，不应产生副作用。
+    pub fn read_consistent<T>(&self, f: impl Fn(&ValueTable) -> T) -> T {
+        loop {
+            let v1 = self.version.load(Ordering::Acquire);
+            if v1 & 1 == 0 {
+                let r = f(self);
+                fence(Ordering::Acquire);
+                if self.version.load(Ordering::Relaxed) == v1 {
+                    return r;
+                }
+            }
+            relax();
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use core::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn no_torn_reads() {
+        let vt = Arc::new(ValueTable::new());
+        let done = Arc::new(AtomicBool::new(false));
+        let writer = {
+            let (vt, done) = (vt.clone(), done.clone());
+            thread::spawn(move || {
+                for i in 0..500u32 {
+                    let val = if i % 2 == 0 { 0xFFFF } else { 0x0000 };
+                    vt.write_consistent(|t| {
+                        t.set_u16(0x0010, val);
+                        thread::yield_now();
+                        t.set_u16(0x0011, val);
+                    });
+                }
+                done.store(true, Ordering::Release);
+            })
+        };
+        while !done.load(Ordering::Acquire) {
+            let v =
+                vt.read_consistent(|t| t.get_u16_pair_as_u32(0x0010, crate::ByteOrder::BigEndian));
+            assert!(v == 0 || v == u32::MAX, "torn read {:#x}", v);
+            thread::yield_now();
+        }
+        writer.join().unwrap();
+        assert_eq!(
+            vt.read_consistent(|t| t.get_u16s(0x0010, 2).to_vec()),
+            [0, 0]
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{Region, ValueTable};
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// 序列化时各区域对应的字段名。
+const FIELDS: [&str; 6] = ["bits", "bits8", "bits16", "bits32", "bits64", "bits128"];
+
+/// 序列化时各字段对应的区域。
+const REGIONS: [Region; 6] = [
+    Region::Bit,
+    Region::Bits8,
+    Region::Bits16,
+    Region::Bits32,
+    Region::Bits64,
+    Region::Bits128,
+];
+
+/// 以字节串形式序列化的区域内容。
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// 反序列化得到的区域内容，同时接受字节串与字节序列两种表示。
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteBufVisitor;
+
+        impl<'de> Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a byte buffer")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ByteBuf, A::Error> {
+                let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(b) = seq.next_element()? {
+                    v.push(b);
+                }
+                Ok(ByteBuf(v))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+/// 序列化为包含各区域原始字节的结构，字节按主机字节序保存。
+impl Serialize for ValueTable {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("ValueTable", FIELDS.len())?;
+        for (name, region) in FIELDS.iter().zip(REGIONS) {
+            s.serialize_field(name, &Bytes(self.region_bytes(region)))?;
+        }
+        s.end()
+    }
+}
+
+/// 从各区域的原始字节重建存储表，任一区域长度不符时返回错误。
+///
+/// 以结构字段形式保存时，缺少 `bits128` 字段视为该区域全零，以兼容加入 128 位区域之前序列化的数据。
+impl<'de> Deserialize<'de> for ValueTable {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ValueTableVisitor;
+
+        impl ValueTableVisitor {
+            fn load<E: de::Error>(vt: &ValueTable, region: Region, buf: ByteBuf) -> Result<(), E> {
+                if buf.0.len() != vt.region_len(region) {
+                    return Err(E::invalid_length(buf.0.len(), &"the region size"));
+                }
+                unsafe {
+                    core::ptr::copy_nonoverlapping(
+                        buf.0.as_ptr(),
+                        vt.region_ptr(region),
+                        buf.0.len(),
+                    );
+                }
+                Ok(())
+            }
+        }
+
+        impl<'de> Visitor<'de> for ValueTableVisitor {
+            type Value = ValueTable;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("struct ValueTable")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ValueTable, A::Error> {
+                let vt = ValueTable::new();
+                for (i, region) in REGIONS.iter().enumerate() {
+                    let buf = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                    Self::load(&vt, *region, buf)?;
+                }
+                Ok(vt)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<ValueTable, A::Error> {
+                let vt = ValueTable::new();
+                let mut seen = [false; 6];
+                while let Some(key) = map.next_key::<String>()? {
+                    let i = FIELDS
+                        .iter()
+                        .position(|f| *f == key)
+                        .ok_or_else(|| de::Error::unknown_field(&key, &FIELDS))?;
+                    if seen[i] {
+                        return Err(de::Error::duplicate_field(FIELDS[i]));
+                    }
+                    seen[i] = true;
+                    Self::load(&vt, REGIONS[i], map.next_value()?)?;
+                }
+                if let Some(i) = seen[..5].iter().position(|s| !s) {
+                    return Err(de::Error::missing_field(FIELDS[i]));
+                }
+                Ok(vt)
+            }
+        }
+
+        deserializer.deserialize_struct("ValueTable", &FIELDS, ValueTableVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn populated() -> ValueTable {
+        let vt = ValueTable::new();
+        for a in [0x0000, 0x0041, 0x1234, 0xFFFF] {
+            vt.set_bit(a);
+        }
+        vt.set_u8(0x0010, 0xAB);
+        vt.set_i16(0x8000, -2);
+        vt.set_u32(0xFFFF, 0xDEAD_BEEF);
+        vt.set_f64(0x0100, 3.25);
+        vt
+    }
+
+    fn assert_same(a: &ValueTable, b: &ValueTable) {
+        for addr in (0..=u16::MAX).step_by(97).chain([0x0041, 0x1234, 0xFFFF]) {
+            assert_eq!(a.get_bit(addr), b.get_bit(addr));
+            assert_eq!(a.get_u8(addr), b.get_u8(addr));
+            assert_eq!(a.get_u16(addr), b.get_u16(addr));
+            assert_eq!(a.get_u32(addr), b.get_u32(addr));
+            assert_eq!(a.get_u64(addr), b.get_u64(addr));
+            assert_eq!(a.get_u128(addr), b.get_u128(addr));
+        }
+        assert_eq!(b.get_i16(0x8000), -2);
+        assert_eq!(b.get_f64(0x0100), 3.25);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let vt = populated();
+        let json = serde_json::to_string(&vt).unwrap();
+        let back: ValueTable = serde_json::from_str(&json).unwrap();
+        assert_same(&vt, &back);
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let vt = populated();
+        let bin = bincode::serialize(&vt).unwrap();
+        let back: ValueTable = bincode::deserialize(&bin).unwrap();
+        assert_same(&vt, &back);
+    }
+
+    #[test]
+    fn wrong_length() {
+        let json = r#"{"bits":[1,2,3],"bits8":[],"bits16":[],"bits32":[],"bits64":[]}"#;
+        assert!(serde_json::from_str::<ValueTable>(json).is_err());
+    }
+
+    #[test]
+    fn without_bits128() {
+        let vt = populated();
+        vt.set_u128(0x0001, 1);
+        let mut json: serde_json::Value = serde_json::to_value(&vt).unwrap();
+        json.as_object_mut().unwrap().remove("bits128");
+        let back: ValueTable = serde_json::from_value(json).unwrap();
+        assert_same(&vt, &back);
+        assert_eq!(back.get_u128(0x0001), 0);
+    }
+}
@@ -0,0 +1,285 @@
+use crate::{Region, ValueTable, XvtError};
+use alloc::{boxed::Box, vec::Vec};
+
+impl ValueTable {
+    /// 复制整个存储表的原始字节作为快照。
+    pub fn snapshot(&self) -> Box<[u8]> {
+        self.as_bytes().into()
+    }
+
+    /// 以快照 `snapshot` 覆盖整个存储表，快照长度与存储表大小不符时返回错误。
+    pub fn restore(&self, snapshot: &[u8]) -> Result<(), XvtError> {
+        let size = self.layout.size();
+        if snapshot.len() != size {
+            return Err(XvtError::LengthMismatch {
+                expected: size,
+                actual: snapshot.len(),
+            });
+        }
+        unsafe {
+            core::ptr::copy_nonoverlapping(snapshot.as_ptr(), self.mem.as_ptr(), size);
+        }
+        self.generations.bump_all();
+        Ok(())
+    }
+
+    /// 以完整布局的原始字节 `bytes`（例如 [`ValueTable::snapshot`] 的结果）构造新的存储表。
+    ///
+    /// 与 [`ValueTable::restore`] 不同，本函数分配新的存储表而不是覆盖已有的存储表；
+    /// `bytes` 的长度与完整布局的大小不符时返回错误。
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, XvtError> {
+        if bytes.len() != Self::BUFFER_SIZE {
+            return Err(XvtError::LengthMismatch {
+                expected: Self::BUFFER_SIZE,
+                actual: bytes.len(),
+            });
+        }
+        let vt = Self::new();
+        vt.restore(bytes)?;
+        Ok(vt)
+    }
+
+    /// 交换两个存储表的缓冲区，用于双缓冲：先填充 `other`，再一次性发布到本存储表。
+    ///
+    /// 只交换缓冲区、布局以及描述缓冲区内容的已写入地址记录（参见 [`ValueTable::new_presence_tracked`]），
+    /// 已注册的回调、脏标记跟踪和版本号仍留在原来的存储表上，
+    /// 交换不会触发回调或脏标记，但会使双方所有区域的变化代数加一。需要在多个线程共享的存储表上发布时，使用
+    /// [`SyncValueTable::swap_buffers`](crate::SyncValueTable::swap_buffers)。
+    pub fn swap_buffers(&mut self, other: &mut ValueTable) {
+        core::mem::swap(&mut self.mem, &mut other.mem);
+        core::mem::swap(&mut self.layout, &mut other.layout);
+        core::mem::swap(&mut self.backing, &mut other.backing);
+        core::mem::swap(&mut self.zeroize, &mut other.zeroize);
+        core::mem::swap(&mut self.presence, &mut other.presence);
+        self.generations.bump_all();
+        other.generations.bump_all();
+    }
+
+    /// 找出自快照 `snapshot` 以来发生变化的所有地址，按区域及地址升序排列。
+    ///
+    /// 快照长度与存储表大小不符时，缺失部分视为零。
+    pub fn diff_addrs(&self, snapshot: &[u8]) -> Vec<(Region, u16)> {
+        let mut old = snapshot.to_vec();
+        old.resize(self.layout.size(), 0);
+        let mut changed = Vec::new();
+
+        const W: usize = core::mem::size_of::<usize>();
+        let ofs = self.region_offset(Region::Bit);
+        let cur = self.region_bytes(Region::Bit);
+        let prev = &old[ofs..ofs + cur.len()];
+        for (i, (a, b)) in cur.chunks_exact(W).zip(prev.chunks_exact(W)).enumerate() {
+            let mut x = usize::from_ne_bytes(a.try_into().unwrap())
+                ^ usize::from_ne_bytes(b.try_into().unwrap());
+            while x != 0 {
+                let bit = x.trailing_zeros() as usize;
+                changed.push((Region::Bit, (i * W * 8 + bit) as u16));
+                x &= x - 1;
+            }
+        }
+
+        for region in Region::VALUES {
+            let n = region.element_size();
+            let ofs = self.region_offset(region);
+            let cur = self.region_bytes(region);
+            let prev = &old[ofs..ofs + cur.len()];
+            for (addr, (a, b)) in cur.chunks_exact(n).zip(prev.chunks_exact(n)).enumerate() {
+                if a != b {
+                    changed.push((region, addr as u16));
+                }
+            }
+        }
+        changed
+    }
+
+    /// 将本存储表中与 `dst` 不同的值复制到 `dst`，返回被修改的地址个数。
+    ///
+    /// 单比特区域逐字比较，数值区域按块比较，内容相同的块直接跳过；两个存储表的布局不同时，
+    /// 只比较双方都能容纳的部分。写入 `dst` 会像 `set_*` 系列一样触发其回调和脏标记。
+    pub fn sync_into(&self, dst: &ValueTable) -> usize {
+        const W: usize = core::mem::size_of::<usize>();
+        const CHUNK: usize = 64;
+        let mut changed = 0;
+
+        let src_bits = self.region_bytes(Region::Bit).chunks_exact(W);
+        let dst_ptr = dst.region_ptr(Region::Bit).cast::<usize>();
+        for (i, w) in src_bits.enumerate().take(dst.region_len(Region::Bit) / W) {
+            let val = usize::from_ne_bytes(w.try_into().unwrap());
+            let mut x = unsafe { dst_ptr.add(i).replace(val) } ^ val;
+            changed += x.count_ones() as usize;
+            while x != 0 && dst.hooked() {
+                let addr = i * W * 8 + x.trailing_zeros() as usize;
+                dst.on_written(Region::Bit, addr as u16, true);
+                x &= x - 1;
+            }
+        }
+
+        for region in Region::VALUES {
+            let n = region.element_size();
+            let len = self.region_len(region).min(dst.region_len(region));
+            let src = &self.region_bytes(region)[..len];
+            let dst_ptr = dst.region_ptr(region);
+            for (c, chunk) in src.chunks(CHUNK).enumerate() {
+                let base = c * CHUNK;
+                let old = unsafe { core::slice::from_raw_parts(dst_ptr.add(base), chunk.len()) };
+                if old == chunk {
+                    continue;
+                }
+                for (j, val) in chunk.chunks_exact(n).enumerate() {
+                    let ofs = base + j * n;
+                    let old = unsafe { core::slice::from_raw_parts(dst_ptr.add(ofs), n) };
+                    if old != val {
+                        unsafe {
+                            core::ptr::copy_nonoverlapping(val.as_ptr(), dst_ptr.add(ofs), n)
+                        };
+                        dst.on_written(region, (ofs / n) as u16, true);
+                        changed += 1;
+                    }
+                }
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn snapshot_restore() {
+        let vt = ValueTable::new();
+        vt.set_bit(0x0100);
+        vt.set_u32(0x0020, 0x1234_5678);
+        let snap = vt.snapshot();
+        assert_eq!(snap.len(), ValueTable::BUFFER_SIZE);
+
+        vt.clear_bit(0x0100);
+        vt.set_u32(0x0020, 0);
+        vt.set_u8(0x0001, 1);
+        assert_eq!(vt.restore(&snap), Ok(()));
+        assert!(vt.get_bit(0x0100));
+        assert_eq!(vt.get_u32(0x0020), 0x1234_5678);
+        assert_eq!(vt.get_u8(0x0001), 0);
+
+        assert_eq!(
+            vt.restore(&snap[1..]),
+            Err(XvtError::LengthMismatch {
+                expected: ValueTable::BUFFER_SIZE,
+                actual: ValueTable::BUFFER_SIZE - 1,
+            })
+        );
+    }
+
+    #[test]
+    fn from_bytes() {
+        let vt = ValueTable::new();
+        vt.set_bit(0xFFFF);
+        vt.set_u16(0x0010, 0xBEEF);
+        vt.set_f64(0x0002, -2.5);
+        vt.set_u128(0x0003, u128::MAX);
+        let copy = ValueTable::from_bytes(&vt.snapshot()).unwrap();
+        assert_eq!(copy.as_bytes(), vt.as_bytes());
+        assert!(copy.get_bit(0xFFFF));
+        assert_eq!(copy.get_f64(0x0002), -2.5);
+
+        assert_eq!(
+            ValueTable::from_bytes(&[0; 16]).err(),
+            Some(XvtError::LengthMismatch {
+                expected: ValueTable::BUFFER_SIZE,
+                actual: 16,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_buffers() {
+        let mut vt = ValueTable::new_tracked();
+        vt.set_u16(0x0001, 1);
+        vt.drain_dirty();
+        let mut other = ValueTable::with_regions(crate::RegionConfig {
+            bits16: 4,
+            ..crate::RegionConfig::empty()
+        });
+        other.set_u16(0x0001, 0xBEEF);
+        vt.swap_buffers(&mut other);
+        assert_eq!(vt.get_u16(0x0001), 0xBEEF);
+        assert_eq!(vt.region_capacity(Region::Bits16), 4);
+        assert_eq!(other.get_u16(0x0001), 1);
+        assert_eq!(other.get_u16(0xFFFF), 0);
+        assert!(vt.drain_dirty().is_empty());
+        vt.set_u16(0x0002, 2);
+        assert_eq!(vt.drain_dirty(), [(Region::Bits16, 0x0002)]);
+    }
+
+    #[test]
+    fn swap_buffers_moves_presence() {
+        let mut vt = ValueTable::new_presence_tracked();
+        vt.set_u16(0x0001, 1);
+        let mut other = ValueTable::new_presence_tracked();
+        other.set_u16(0x0002, 2);
+        vt.swap_buffers(&mut other);
+        assert_eq!(vt.get_u16_opt(0x0001), None);
+        assert_eq!(vt.get_u16_opt(0x0002), Some(2));
+        assert_eq!(other.get_u16_opt(0x0001), Some(1));
+        assert_eq!(other.get_u16_opt(0x0002), None);
+
+        let mut plain = ValueTable::new();
+        vt.swap_buffers(&mut plain);
+        assert_eq!(vt.get_u16_opt(0x0003), Some(0));
+        assert_eq!(plain.get_u16_opt(0x0003), None);
+        assert_eq!(plain.get_u16_opt(0x0002), Some(2));
+    }
+
+    #[test]
+    fn sync_into() {
+        let src = ValueTable::new();
+        for addr in [0x0000, 0x0041, 0xFFFF] {
+            src.set_bit(addr);
+        }
+        src.set_u8(0x0010, 1);
+        src.set_u16s(0xFFFD, &[1, 2, 3]);
+        src.set_f32(0x0000, 1.0);
+        src.set_u64(0x8000, u64::MAX);
+        let dst = ValueTable::new_tracked();
+        assert_eq!(src.sync_into(&dst), 9);
+        assert!(dst.as_bytes() == src.as_bytes());
+        assert_eq!(dst.drain_dirty().len(), 9);
+        assert_eq!(src.sync_into(&dst), 0);
+
+        src.clear_bit(0x0041);
+        src.set_u16(0xFFFE, 5);
+        assert_eq!(src.sync_into(&dst), 2);
+        assert!(!dst.get_bit(0x0041));
+        assert_eq!(dst.get_u16(0xFFFE), 5);
+    }
+
+    #[test]
+    fn diff() {
+        let vt = ValueTable::new();
+        vt.set_bit(0x0003);
+        let snap = vt.snapshot();
+        assert!(vt.diff_addrs(&snap).is_empty());
+
+        vt.clear_bit(0x0003);
+        vt.set_bit(0x0040);
+        vt.set_bit(0xFFFF);
+        vt.set_u8(0x0007, 7);
+        vt.set_u16(0xFFFF, 1);
+        vt.set_u32(0x0000, 2);
+        vt.set_i64(0x1000, -3);
+        vt.set_u16(0x0002, 0);
+        assert_eq!(
+            vt.diff_addrs(&snap),
+            vec![
+                (Region::Bit, 0x0003),
+                (Region::Bit, 0x0040),
+                (Region::Bit, 0xFFFF),
+                (Region::Bits8, 0x0007),
+                (Region::Bits16, 0xFFFF),
+                (Region::Bits32, 0x0000),
+                (Region::Bits64, 0x1000),
+            ]
+        );
+    }
+}
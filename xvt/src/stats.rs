@@ -0,0 +1,184 @@
+use crate::{Region, ValueTable};
+use paste::paste;
+
+/// 一个代表数值区域统计结果的类型。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NumericStats {
+    /// 最小值，范围为空时为 `0`。
+    pub min: u64,
+    /// 最大值，范围为空时为 `0`。
+    pub max: u64,
+    /// 所有值之和。
+    pub sum: u64,
+    /// 平均值，范围为空时为 `0.0`。
+    pub mean: f64,
+    /// 非零值的个数。
+    pub nonzero: u32,
+}
+
+impl NumericStats {
+    /// 单次遍历 `vals` 计算统计结果。
+    fn from_values<I: IntoIterator<Item = u64>>(vals: I) -> Self {
+        let mut stats = NumericStats {
+            min: u64::MAX,
+            ..Default::default()
+        };
+        let mut n = 0u64;
+        for v in vals {
+            stats.min = stats.min.min(v);
+            stats.max = stats.max.max(v);
+            stats.sum += v;
+            if v != 0 {
+                stats.nonzero += 1;
+            }
+            n += 1;
+        }
+        if n == 0 {
+            return NumericStats::default();
+        }
+        stats.mean = stats.sum as f64 / n as f64;
+        stats
+    }
+}
+
+/// 一个代表整个整数区域统计报告的类型。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegionStats {
+    /// 参与统计的值个数，即区域容量。
+    pub count: u32,
+    /// 最小值，区域为空时为 `0`。
+    pub min: i128,
+    /// 最大值，区域为空时为 `0`。
+    pub max: i128,
+    /// 所有值之和。
+    pub sum: i128,
+    /// 非零值的个数。
+    pub nonzero: u32,
+    /// 值的分布，将该类型的取值范围从小到大等分为 16 段，依次记录落入各段的值个数。
+    pub histogram: [u32; RegionStats::BUCKETS],
+}
+
+impl RegionStats {
+    /// 分布的段数。
+    pub const BUCKETS: usize = 16;
+}
+
+macro_rules! impl_report {
+    ($t:ty, $u:ty, $n:expr) => {
+        paste! {
+            #[doc = "单次遍历整个 " $n " 位区域，按类型 `" $t "` 统计所有的值。"]
+            pub fn [<region_report_ $t>](&self) -> RegionStats {
+                let region = Region::[<Bits $n>];
+                let vals = unsafe {
+                    core::slice::from_raw_parts(
+                        self.region_ptr(region).cast::<$t>(),
+                        self.region_capacity(region),
+                    )
+                };
+                if vals.is_empty() {
+                    return RegionStats::default();
+                }
+                let mut stats = RegionStats {
+                    count: vals.len() as u32,
+                    min: i128::MAX,
+                    max: i128::MIN,
+                    ..Default::default()
+                };
+                for v in vals {
+                    let x = *v as i128;
+                    stats.min = stats.min.min(x);
+                    stats.max = stats.max.max(x);
+                    stats.sum += x;
+                    if *v != 0 {
+                        stats.nonzero += 1;
+                    }
+                    let bits = (*v as $u) ^ (<$t>::MIN as $u);
+                    stats.histogram[(bits >> ($n - 4)) as usize] += 1;
+                }
+                stats
+            }
+        }
+    };
+}
+
+impl ValueTable {
+    impl_report!(i8, u8, 8);
+    impl_report!(u8, u8, 8);
+    impl_report!(i16, u16, 16);
+    impl_report!(u16, u16, 16);
+    impl_report!(i32, u32, 32);
+    impl_report!(u32, u32, 32);
+    impl_report!(i64, u64, 64);
+    impl_report!(u64, u64, 64);
+
+    /// 统计从地址 `start` 开始 `count` 个类型为 `u16` 的值。
+    ///
+    /// 超出地址范围的部分将被忽略。
+    pub fn region_stats_u16(&self, start: u16, count: u16) -> NumericStats {
+        NumericStats::from_values(self.get_u16s(start, count).iter().map(|v| *v as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_u16() {
+        let vt = ValueTable::new();
+        let vals = [5u16, 0, 17, 65535, 3, 0, 9];
+        vt.set_u16s(0x0200, &vals);
+        let stats = vt.region_stats_u16(0x0200, vals.len() as u16);
+        let sum: u64 = vals.iter().map(|v| *v as u64).sum();
+        assert_eq!(stats.min, 0);
+        assert_eq!(stats.max, 65535);
+        assert_eq!(stats.sum, sum);
+        assert_eq!(stats.mean, sum as f64 / vals.len() as f64);
+        assert_eq!(stats.nonzero, 5);
+
+        let stats = vt.region_stats_u16(0x0202, 2);
+        assert_eq!(
+            (stats.min, stats.max, stats.sum, stats.nonzero),
+            (17, 65535, 65552, 2)
+        );
+        assert_eq!(vt.region_stats_u16(0x0200, 0), NumericStats::default());
+    }
+
+    #[test]
+    fn report_ramp() {
+        let vt = ValueTable::new();
+        for addr in 0..=u16::MAX {
+            vt.set_u16(addr, addr);
+        }
+        let stats = vt.region_report_u16();
+        assert_eq!(stats.count, 65536);
+        assert_eq!((stats.min, stats.max), (0, 65535));
+        assert_eq!(stats.sum, 65535 * 65536 / 2);
+        assert_eq!(stats.nonzero, 65535);
+        assert_eq!(stats.histogram, [4096; RegionStats::BUCKETS]);
+    }
+
+    #[test]
+    fn report_signed() {
+        let vt = ValueTable::new();
+        vt.set_i32s(0x0000, &[i32::MIN, -1, 7, i32::MAX]);
+        let stats = vt.region_report_i32();
+        assert_eq!((stats.min, stats.max), (i32::MIN as i128, i32::MAX as i128));
+        assert_eq!(stats.sum, -1 + 7 - 1);
+        assert_eq!(stats.nonzero, 4);
+        assert_eq!(stats.histogram[0], 1);
+        assert_eq!(stats.histogram[7], 1);
+        assert_eq!(stats.histogram[8], 65533);
+        assert_eq!(stats.histogram[15], 1);
+
+        let vt = ValueTable::new();
+        vt.set_u64(0xFFFF, u64::MAX);
+        let stats = vt.region_report_u64();
+        assert_eq!(stats.sum, u64::MAX as i128);
+        assert_eq!(stats.histogram[15], 1);
+        assert_eq!(
+            ValueTable::with_regions(crate::RegionConfig::empty()).region_report_u8(),
+            RegionStats::default()
+        );
+    }
+}
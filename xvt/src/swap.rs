@@ -0,0 +1,202 @@
+use crate::{Region, ValueTable};
+use paste::paste;
+
+/// 将 `src` 中每个 `u16` 值交换字节序后写入 `dst`，逐个值处理。
+///
+/// 只处理两者中较短的部分，是 [`swap_bytes_u16`] 的标量参考实现。
+pub fn swap_bytes_u16_scalar(dst: &mut [u16], src: &[u16]) {
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = s.swap_bytes();
+    }
+}
+
+/// 将 `src` 中每个 `u16` 值交换字节序后写入 `dst`，用于在大端的 Modbus 数据与主机字节序之间转换。
+///
+/// 只处理两者中较短的部分。开启 `simd` 特性且运行在支持 AVX2 的 `x86_64` 处理器上时，
+/// 每次处理 16 个值，否则回退到 [`swap_bytes_u16_scalar`]。
+pub fn swap_bytes_u16(dst: &mut [u16], src: &[u16]) {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if avx2::detected() {
+        unsafe { avx2::swap_bytes_u16(dst, src) };
+        return;
+    }
+    swap_bytes_u16_scalar(dst, src)
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod avx2 {
+    use core::arch::x86_64::*;
+
+    /// 当前处理器是否支持 AVX2。
+    #[cfg(feature = "std")]
+    pub(super) fn detected() -> bool {
+        is_x86_feature_detected!("avx2")
+    }
+
+    /// 未启用 `std` 时无法在运行时检测，仅看编译目标是否开启了 AVX2。
+    #[cfg(not(feature = "std"))]
+    pub(super) fn detected() -> bool {
+        cfg!(target_feature = "avx2")
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn swap_bytes_u16(dst: &mut [u16], src: &[u16]) {
+        let n = dst.len().min(src.len());
+        let mask = _mm256_setr_epi8(
+            1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10, 13, 12, 15, 14, //
+            1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10, 13, 12, 15, 14,
+        );
+        let mut i = 0;
+        while i + 16 <= n {
+            let v = _mm256_loadu_si256(src.as_ptr().add(i).cast());
+            _mm256_storeu_si256(dst.as_mut_ptr().add(i).cast(), _mm256_shuffle_epi8(v, mask));
+            i += 16;
+        }
+        super::swap_bytes_u16_scalar(&mut dst[i..n], &src[i..n]);
+    }
+}
+
+macro_rules! impl_swap_region {
+    ($t:ty, $n:expr) => {
+        paste! {
+            #[doc = "原地交换 " $n " 位区域中每个值的字节序，用于在大端与小端的存储之间整体转换。"]
+            ///
+            /// 开启了脏标记跟踪或注册了回调时逐个值写入，像 `set_*` 系列一样触发回调和脏标记。
+            pub fn [<swap_bytes_region_ $t>](&self) {
+                let region = Region::[<Bits $n>];
+                let cap = self.region_capacity(region);
+                if self.hooked() {
+                    for addr in 0..cap {
+                        let addr = addr as u16;
+                        self.[<set_ $t>](addr, self.[<get_ $t>](addr).swap_bytes());
+                    }
+                    return;
+                }
+                let vals = unsafe {
+                    core::slice::from_raw_parts_mut(self.region_ptr(region).cast::<$t>(), cap)
+                };
+                vals.iter_mut().for_each(|v| *v = v.swap_bytes());
+            }
+        }
+    };
+}
+
+impl ValueTable {
+    impl_swap_region!(u16, 16);
+    impl_swap_region!(u32, 32);
+    impl_swap_region!(u64, 64);
+    impl_swap_region!(u128, 128);
+
+    /// 设置指定地址 `addr` 类型为 `u16` 的多个值，每个值写入前交换字节序，返回实际写入的值个数。
+    ///
+    /// 超出区域容量的部分将被忽略；`vals` 可以是存储表自身的一部分。
+    pub fn set_u16s_swapped(&self, addr: u16, vals: &[u16]) -> usize {
+        let cap = self.region_capacity(Region::Bits16);
+        let n = cap.saturating_sub(addr as usize).min(vals.len());
+        if n == 0 {
+            return 0;
+        }
+        let dst_ptr = unsafe {
+            self.region_ptr(Region::Bits16)
+                .cast::<u16>()
+                .add(addr as usize)
+        };
+        let src = vals[..n].as_ptr_range();
+        let dst = dst_ptr.cast_const()..dst_ptr.cast_const().wrapping_add(n);
+        if src.start < dst.end && dst.start < src.end {
+            let vals = vals[..n].to_vec();
+            return self.set_u16s_swapped(addr, &vals);
+        }
+        if self.hooked() {
+            for (i, val) in vals[..n].iter().enumerate() {
+                self.set_u16(addr + i as u16, val.swap_bytes());
+            }
+            return n;
+        }
+        let dst = unsafe { core::slice::from_raw_parts_mut(dst_ptr, n) };
+        swap_bytes_u16(dst, &vals[..n]);
+        n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+
+    #[test]
+    fn swap_region() {
+        let vt = ValueTable::new();
+        for addr in 0..=u16::MAX {
+            vt.set_u16(addr, addr.wrapping_mul(31));
+            vt.set_u64(addr, (addr as u64) << 40 | 0x1234);
+        }
+        vt.swap_bytes_region_u16();
+        vt.swap_bytes_region_u64();
+        for addr in 0..=u16::MAX {
+            assert_eq!(vt.get_u16(addr), addr.wrapping_mul(31).swap_bytes());
+            assert_eq!(
+                vt.get_u64(addr),
+                ((addr as u64) << 40 | 0x1234).swap_bytes()
+            );
+        }
+        vt.swap_bytes_region_u16();
+        vt.swap_bytes_region_u64();
+        assert_eq!(vt.get_u16(0x0101), 0x0101u16.wrapping_mul(31));
+        assert_eq!(vt.get_u64(0xFFFF), 0xFFFF << 40 | 0x1234);
+
+        let vt = ValueTable::new_tracked();
+        vt.set_u32(0x0002, 0x1122_3344);
+        vt.drain_dirty();
+        vt.swap_bytes_region_u32();
+        assert_eq!(vt.get_u32(0x0002), 0x4433_2211);
+        assert_eq!(vt.drain_dirty().len(), 65536);
+    }
+
+    #[test]
+    fn matches_scalar() {
+        let src: Vec<u16> = (0..65536u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 7) as u16)
+            .collect();
+        for len in [0, 1, 15, 16, 17, 33, 65536] {
+            let mut a = vec![0u16; len];
+            let mut b = vec![0u16; len];
+            swap_bytes_u16_scalar(&mut a, &src[..len]);
+            swap_bytes_u16(&mut b, &src[..len]);
+            assert_eq!(a, b);
+        }
+        assert_eq!(src[1].swap_bytes(), {
+            let mut d = [0u16; 1];
+            swap_bytes_u16(&mut d, &src[1..]);
+            d[0]
+        });
+    }
+
+    #[test]
+    fn set_swapped() {
+        let vt = ValueTable::new();
+        assert_eq!(vt.set_u16s_swapped(0xFFFE, &[0x1234, 0x5678, 0x9ABC]), 2);
+        assert_eq!(vt.get_u16s(0xFFFE, 2), &[0x3412, 0x7856]);
+        let vals: Vec<u16> = (0..40).collect();
+        assert_eq!(vt.set_u16s_swapped(0x0000, &vals), 40);
+        assert_eq!(vt.get_u16(0x0027), 0x2700);
+        let own = vt.get_u16s(0x0000, 40);
+        assert_eq!(vt.set_u16s_swapped(0x0001, own), 40);
+        assert_eq!(vt.get_u16s(0x0000, 3), &[0x0000, 0x0000, 0x0001]);
+        assert_eq!(vt.get_u16(0x0028), 39);
+
+        let vt = ValueTable::new_tracked();
+        vt.set_u16s(0x0000, &[0x0100, 0x0101, 0x0102, 0x0103]);
+        vt.drain_dirty();
+        assert_eq!(vt.set_u16s_swapped(0x0001, vt.get_u16s(0x0000, 4)), 4);
+        assert_eq!(
+            vt.get_u16s(0x0000, 5),
+            &[0x0100, 0x0001, 0x0101, 0x0201, 0x0301]
+        );
+        assert_eq!(vt.drain_dirty().len(), 4);
+        let plain = ValueTable::new();
+        plain.set_u16s(0x0002, &[0x0100, 0x0101]);
+        assert_eq!(plain.set_u16s_swapped(0x0000, plain.get_u16s(0x0002, 2)), 2);
+        assert_eq!(plain.get_u16s(0x0000, 4), &[0x0001, 0x0101, 0x0100, 0x0101]);
+    }
+}
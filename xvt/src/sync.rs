@@ -0,0 +1,197 @@
+use crate::{Region, ValueTable};
+use std::sync::RwLock;
+
+/// 一个以读写锁保护的多种值存储表类型。
+///
+/// 与 [`ValueTable`] 不同，本类型提供的读写方法均经过读写锁同步，可以安全地在多个线程间共享。
+///
+/// [`SyncValueTable::with_read`] 交给闭包的是 `&ValueTable`，其 `set_*` 系列同样可以调用，但只持有读锁，
+/// 多个线程同时在其中写入仍会产生数据竞争；所有修改都应通过 [`SyncValueTable::with_write`]
+/// 或本类型的写入方法进行。
+///
+/// # Examples
+///
+/// ```
+/// use xvt::{Region, SyncValueTable};
+///
+/// let vt = SyncValueTable::new();
+/// vt.with_u16_mut(Region::Bits16, |vals| vals[..4].copy_from_slice(&[4, 3, 2, 1]));
+/// vt.with_u16_mut(Region::Bits16, |vals| vals[..4].sort());
+/// ```
+pub struct SyncValueTable {
+    inner: RwLock<ValueTable>,
+}
+
+impl SyncValueTable {
+    /// 构建一个以读写锁保护的多种值存储表实例。
+    pub fn new() -> Self {
+        Self::from(ValueTable::new())
+    }
+
+    /// 获取读锁，并将存储表交给 `f` 读取。
+    ///
+    /// `f` 执行期间不会有其他线程经写锁写入，可用于一次性读取多个相关的值。`f` 只应读取存储表，
+    /// 需要修改时使用 [`SyncValueTable::with_write`]。
+    pub fn with_read<R>(&self, f: impl FnOnce(&ValueTable) -> R) -> R {
+        let vt = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        f(&vt)
+    }
+
+    /// 获取写锁，并将存储表交给 `f` 修改。
+    ///
+    /// `f` 执行期间不会有其他线程读取或写入，可用于以事务方式修改多个相关的值。
+    pub fn with_write<R>(&self, f: impl FnOnce(&mut ValueTable) -> R) -> R {
+        let mut vt = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        f(&mut vt)
+    }
+
+    /// 获取指定地址 `addr` 的单比特值。
+    pub fn read_bit(&self, addr: u16) -> bool {
+        self.with_read(|vt| vt.get_bit(addr))
+    }
+
+    /// 设置或清除指定地址 `addr` 的单比特值。
+    pub fn write_bit(&self, addr: u16, val: bool) {
+        self.with_write(|vt| {
+            if val {
+                vt.set_bit(addr)
+            } else {
+                vt.clear_bit(addr)
+            }
+        })
+    }
+
+    /// 获取写锁，并以类型为 `u16` 的可变切片形式将区域 `region` 交给 `f` 处理。
+    pub fn with_u16_mut<R>(&self, region: Region, f: impl FnOnce(&mut [u16]) -> R) -> R {
+        let mut vt = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        vt.generations.bump(region);
+        f(vt.region_u16s_mut(region))
+    }
+
+    /// 获取写锁，并与 `other` 交换缓冲区，参见 [`ValueTable::swap_buffers`]。
+    ///
+    /// 读取方要么看到交换前的全部内容，要么看到交换后的全部内容，不会看到两者的混合。
+    pub fn swap_buffers(&self, other: &mut ValueTable) {
+        self.with_write(|vt| vt.swap_buffers(other))
+    }
+
+    /// 获取读锁，并以类型为 `u16` 的值复制区域 `region` 的全部内容。
+    ///
+    /// 返回的副本与存储表无关，且反映了某一时刻完整一致的区域状态。
+    pub fn snapshot_region_consistent_u16(&self, region: Region) -> Vec<u16> {
+        let vt = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        vt.region_u16s(region).to_vec()
+    }
+}
+
+impl Default for SyncValueTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<ValueTable> for SyncValueTable {
+    fn from(vt: ValueTable) -> Self {
+        Self {
+            inner: RwLock::new(vt),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn bits() {
+        let vt = SyncValueTable::new();
+        vt.write_bit(0xFFFF, true);
+        assert!(vt.read_bit(0xFFFF));
+        vt.write_bit(0xFFFF, false);
+        assert!(!vt.read_bit(0xFFFF));
+    }
+
+    #[test]
+    fn read_write_serialize() {
+        let vt = Arc::new(SyncValueTable::new());
+        let writer = {
+            let vt = vt.clone();
+            thread::spawn(move || {
+                for i in 1..=200u32 {
+                    vt.with_write(|t| {
+                        t.set_u32(0x0000, i);
+                        thread::yield_now();
+                        t.set_u32(0x0001, i);
+                    });
+                }
+            })
+        };
+        for _ in 0..200 {
+            let (a, b) = vt.with_read(|t| (t.get_u32(0x0000), t.get_u32(0x0001)));
+            assert_eq!(a, b);
+            thread::yield_now();
+        }
+        writer.join().unwrap();
+        assert_eq!(vt.with_read(|t| t.get_u32(0x0001)), 200);
+    }
+
+    #[test]
+    fn swap_buffers() {
+        let vt = Arc::new(SyncValueTable::new());
+        let publisher = {
+            let vt = vt.clone();
+            thread::spawn(move || {
+                let mut scratch = ValueTable::new();
+                for k in 1..=20u16 {
+                    scratch.fill_u16(k);
+                    vt.swap_buffers(&mut scratch);
+                    thread::yield_now();
+                }
+            })
+        };
+        for _ in 0..20 {
+            let (a, b) = vt.with_read(|t| (t.get_u16(0x0000), t.get_u16(0xFFFF)));
+            assert_eq!(a, b);
+            thread::yield_now();
+        }
+        publisher.join().unwrap();
+        assert_eq!(vt.with_read(|t| t.get_u16(0x1234)), 20);
+    }
+
+    #[test]
+    fn u16_mut() {
+        let vt = SyncValueTable::new();
+        let vals = [9u16, 1, 8, 2, 7, 3];
+        vt.with_u16_mut(Region::Bits16, |s| s[0x10..0x16].copy_from_slice(&vals));
+        vt.with_u16_mut(Region::Bits16, |s| s[0x10..0x16].sort_unstable());
+        let sorted = vt.with_u16_mut(Region::Bits16, |s| s[0x10..0x16].to_vec());
+        assert_eq!(sorted, [1, 2, 3, 7, 8, 9]);
+        let len = vt.with_u16_mut(Region::Bits32, |s| s.len());
+        assert_eq!(len, 131072);
+    }
+
+    #[test]
+    fn consistent_snapshot() {
+        let vt = Arc::new(SyncValueTable::new());
+        let writer = {
+            let vt = vt.clone();
+            thread::spawn(move || {
+                for k in 1..=20u16 {
+                    vt.with_u16_mut(Region::Bits16, |s| s.fill(k));
+                    thread::yield_now();
+                }
+            })
+        };
+        for _ in 0..20 {
+            let snap = vt.snapshot_region_consistent_u16(Region::Bits16);
+            assert_eq!(snap.len(), 65536);
+            assert!(snap.iter().all(|v| *v == snap[0]));
+            thread::yield_now();
+        }
+        writer.join().unwrap();
+        let snap = vt.snapshot_region_consistent_u16(Region::Bits16);
+        assert!(snap.iter().all(|v| *v == 20));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{Region, ValueTable};
+use core::ops::Index;
+
+/// 一个限定在 16 位区域上的存储表视图，由 [`ValueTable::u16_region`] 返回。
+///
+/// 视图只借用存储表，所有访问都等同于调用对应的 `*_u16` 方法。由于写入经由 `&self` 进行，
+/// 视图只实现了 [`Index`]；需要可变切片时使用 [`ValueTable::region_mut_u16`]。
+///
+/// # Examples
+///
+/// ```
+/// use xvt::ValueTable;
+///
+/// let vt = ValueTable::new();
+/// let regs = vt.u16_region();
+/// regs.set(0x0010, 7);
+/// assert_eq!(regs[0x0010], 7);
+/// assert_eq!(vt.get_u16(0x0010), 7);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct U16View<'a> {
+    vt: &'a ValueTable,
+}
+
+impl<'a> U16View<'a> {
+    /// 获取指定地址 `addr` 的值，见 [`ValueTable::get_u16`]。
+    pub fn get(&self, addr: u16) -> u16 {
+        self.vt.get_u16(addr)
+    }
+
+    /// 设置指定地址 `addr` 的值，见 [`ValueTable::set_u16`]。
+    pub fn set(&self, addr: u16, val: u16) {
+        self.vt.set_u16(addr, val)
+    }
+
+    /// 获取从 `addr` 开始的 `num` 个值，见 [`ValueTable::get_u16s`]。
+    pub fn get_range(&self, addr: u16, num: u16) -> &'a [u16] {
+        self.vt.get_u16s(addr, num)
+    }
+
+    /// 从 `addr` 开始写入多个值，返回实际写入的值个数，见 [`ValueTable::set_u16s`]。
+    pub fn set_range(&self, addr: u16, vals: &[u16]) -> usize {
+        self.vt.set_u16s(addr, vals)
+    }
+
+    /// 区域可容纳的值个数。
+    pub fn len(&self) -> usize {
+        self.vt.region_capacity(Region::Bits16)
+    }
+
+    /// 区域是否未分配。
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 超出区域容量的地址读作 `0`，与 [`ValueTable::get_u16`] 一致。
+impl Index<u16> for U16View<'_> {
+    type Output = u16;
+
+    fn index(&self, addr: u16) -> &u16 {
+        self.vt
+            .region_u16s(Region::Bits16)
+            .get(addr as usize)
+            .unwrap_or(&0)
+    }
+}
+
+impl ValueTable {
+    /// 获取限定在 16 位区域上的视图，便于在同一区域内反复访问。
+    pub fn u16_region(&self) -> U16View<'_> {
+        U16View { vt: self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RegionConfig;
+
+    #[test]
+    fn matches_direct_api() {
+        let vt = ValueTable::new();
+        let regs = vt.u16_region();
+        regs.set(0x0000, 1);
+        regs.set(0xFFFF, 0xBEEF);
+        assert_eq!(regs.set_range(0x0010, &[4, 5, 6]), 3);
+        assert_eq!(vt.get_u16(0x0000), 1);
+        assert_eq!(vt.get_u16(0xFFFF), 0xBEEF);
+        vt.set_u16(0x0100, 9);
+        assert_eq!(regs.get(0x0100), 9);
+        assert_eq!(regs[0x0100], 9);
+        assert_eq!(regs.get_range(0x0010, 3), vt.get_u16s(0x0010, 3));
+        assert_eq!(regs.len(), 65536);
+    }
+
+    #[test]
+    fn out_of_capacity() {
+        let vt = ValueTable::with_regions(RegionConfig {
+            bits16: 2,
+            ..RegionConfig::empty()
+        });
+        let regs = vt.u16_region();
+        regs.set(0x0002, 5);
+        assert_eq!(regs[0x0002], 0);
+        assert_eq!(regs.get_range(0x0001, 4).len(), 1);
+        assert!(ValueTable::with_regions(RegionConfig::empty())
+            .u16_region()
+            .is_empty());
+    }
+}
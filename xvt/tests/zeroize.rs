@@ -0,0 +1,46 @@
+//! 通过自定义全局分配器检查清零存储表在释放前确实覆盖了缓冲区。
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use xvt::ValueTable;
+
+/// 完整布局下存储表缓冲区的大小。
+const BUFFER_SIZE: usize = 2 * 1024 * 1024;
+
+/// 记录被释放的存储表缓冲区中非零字节个数的分配器。
+struct Inspect;
+
+static DIRTY_BYTES: AtomicUsize = AtomicUsize::new(0);
+static BUFFERS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Inspect {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if layout.size() == BUFFER_SIZE && layout.align() == 4096 {
+            let bytes = std::slice::from_raw_parts(ptr, layout.size());
+            let dirty = bytes.iter().filter(|b| **b != 0).count();
+            DIRTY_BYTES.fetch_add(dirty, Ordering::SeqCst);
+            BUFFERS.fetch_add(1, Ordering::SeqCst);
+        }
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Inspect = Inspect;
+
+#[test]
+fn wiped_on_drop() {
+    let vt = ValueTable::new_zeroizing();
+    vt.set_bit(0x0001);
+    vt.set_u32(0x0010, 0xDEAD_BEEF);
+    vt.set_u128(0xFFFF, u128::MAX);
+    let copy = vt.clone();
+    drop(vt);
+    drop(copy);
+    assert_eq!(BUFFERS.load(Ordering::SeqCst), 2);
+    assert_eq!(DIRTY_BYTES.load(Ordering::SeqCst), 0);
+}